@@ -0,0 +1 @@
+CREATE INDEX idx_articles_published_created ON articles (published, created_at, id);
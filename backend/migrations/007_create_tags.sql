@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS tags (
+    id BINARY(16) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    slug VARCHAR(100) NOT NULL UNIQUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS article_tags (
+    article_id BINARY(16) NOT NULL,
+    tag_id BINARY(16) NOT NULL,
+    PRIMARY KEY (article_id, tag_id),
+    INDEX idx_article_tags_tag_id (tag_id),
+    FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,2 @@
+ALTER TABLE articles ADD FULLTEXT INDEX ft_articles_title_markdown (title, markdown);
+ALTER TABLE books ADD FULLTEXT INDEX ft_books_title_markdown (title, markdown);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS article_revisions (
+    id BINARY(16) NOT NULL,
+    article_id BINARY(16) NOT NULL,
+    revision_number INT NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    markdown LONGTEXT NOT NULL,
+    created_by BINARY(16),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE KEY uq_article_revisions_number (article_id, revision_number),
+    FOREIGN KEY (article_id) REFERENCES articles(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
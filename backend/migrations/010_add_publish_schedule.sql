@@ -0,0 +1,12 @@
+ALTER TABLE articles
+    ADD COLUMN publish_at TIMESTAMP NULL DEFAULT NULL AFTER published,
+    ADD COLUMN published_at TIMESTAMP NULL DEFAULT NULL AFTER publish_at,
+    ADD INDEX idx_articles_publish_at (publish_at);
+
+ALTER TABLE books
+    ADD COLUMN publish_at TIMESTAMP NULL DEFAULT NULL AFTER published,
+    ADD COLUMN published_at TIMESTAMP NULL DEFAULT NULL AFTER publish_at,
+    ADD INDEX idx_books_publish_at (publish_at);
+
+UPDATE articles SET published_at = created_at WHERE published = true;
+UPDATE books SET published_at = created_at WHERE published = true;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS slug_history (
+    entity_type VARCHAR(16) NOT NULL,
+    slug VARCHAR(255) NOT NULL,
+    entity_id BINARY(16) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (entity_type, slug),
+    INDEX idx_slug_history_entity (entity_type, entity_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
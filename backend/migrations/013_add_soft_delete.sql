@@ -0,0 +1,7 @@
+ALTER TABLE articles
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL,
+    ADD INDEX idx_articles_deleted_at (deleted_at);
+
+ALTER TABLE books
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL,
+    ADD INDEX idx_books_deleted_at (deleted_at);
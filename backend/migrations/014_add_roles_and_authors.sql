@@ -0,0 +1,13 @@
+-- Users that existed before roles keep the full access they already had
+ALTER TABLE users ADD COLUMN role VARCHAR(16) NOT NULL DEFAULT 'viewer' AFTER password_hash;
+UPDATE users SET role = 'admin';
+
+ALTER TABLE articles
+    ADD COLUMN author_id BINARY(16) NULL DEFAULT NULL AFTER id,
+    ADD INDEX idx_articles_author_id (author_id),
+    ADD CONSTRAINT fk_articles_author FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE books
+    ADD COLUMN author_id BINARY(16) NULL DEFAULT NULL AFTER id,
+    ADD INDEX idx_books_author_id (author_id),
+    ADD CONSTRAINT fk_books_author FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,27 @@
+ALTER TABLE users
+    ADD COLUMN totp_secret VARCHAR(64) NULL DEFAULT NULL,
+    ADD COLUMN totp_enabled_at TIMESTAMP NULL DEFAULT NULL,
+    ADD COLUMN totp_last_step BIGINT NULL DEFAULT NULL;
+
+CREATE TABLE IF NOT EXISTS recovery_codes (
+    id BINARY(16) NOT NULL,
+    user_id BINARY(16) NOT NULL,
+    code_hash CHAR(64) NOT NULL,
+    used_at TIMESTAMP NULL DEFAULT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id),
+    INDEX idx_recovery_codes_user_id (user_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- Password checked, second factor still outstanding
+CREATE TABLE IF NOT EXISTS pending_logins (
+    id BINARY(16) NOT NULL,
+    user_id BINARY(16) NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id),
+    INDEX idx_pending_logins_expires_at (expires_at),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,4 @@
+ALTER TABLE sessions
+    ADD COLUMN user_agent VARCHAR(512) NULL DEFAULT NULL,
+    ADD COLUMN ip_address VARCHAR(45) NULL DEFAULT NULL,
+    ADD COLUMN last_seen_at TIMESTAMP NULL DEFAULT NULL;
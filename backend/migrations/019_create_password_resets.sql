@@ -0,0 +1,13 @@
+-- Single-use links for the forgot-password flow; only a SHA-256 of the token is kept
+CREATE TABLE IF NOT EXISTS password_reset_tokens (
+    id BINARY(16) NOT NULL,
+    user_id BINARY(16) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP NULL DEFAULT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id),
+    UNIQUE KEY uq_password_reset_tokens_hash (token_hash),
+    INDEX idx_password_reset_tokens_user_id (user_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
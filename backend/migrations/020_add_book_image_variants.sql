@@ -0,0 +1,3 @@
+-- Responsive renditions of the cover image; see models::image::ImageVariants
+ALTER TABLE books
+    ADD COLUMN image_variants JSON NULL DEFAULT NULL AFTER image_url;
@@ -0,0 +1,5 @@
+-- The default parser only splits on whitespace, so CJK text never matched; ngram indexes character bigrams
+ALTER TABLE articles DROP INDEX ft_articles_title_markdown;
+ALTER TABLE articles ADD FULLTEXT INDEX ft_articles_title_markdown (title, markdown) WITH PARSER ngram;
+ALTER TABLE books DROP INDEX ft_books_title_markdown;
+ALTER TABLE books ADD FULLTEXT INDEX ft_books_title_markdown (title, markdown) WITH PARSER ngram;
@@ -0,0 +1,92 @@
+use std::ops::Deref;
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use sqlx::MySqlPool;
+use crate::error::ApiError;
+use crate::models::user::{Role, User};
+
+/// The signed-in user that `require_auth` put in the request extensions, with the role
+/// checks handlers apply before acting.
+#[derive(Debug, Clone)]
+pub struct CurrentUser(pub User);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<User>()
+            .cloned()
+            .map(CurrentUser)
+            .ok_or_else(|| ApiError::unauthorized("unauthorized"))
+    }
+}
+
+impl Deref for CurrentUser {
+    type Target = User;
+
+    fn deref(&self) -> &User {
+        &self.0
+    }
+}
+
+fn deny(role: Role, action: &str) -> ApiError {
+    ApiError::forbidden(format!("The {} role may not {}", role, action))
+}
+
+impl CurrentUser {
+    pub fn require_create(&self) -> Result<(), ApiError> {
+        if self.role.can_create_content() {
+            Ok(())
+        } else {
+            Err(deny(self.role, "create content"))
+        }
+    }
+
+    pub fn require_manage_tags(&self) -> Result<(), ApiError> {
+        if self.role.can_manage_tags() {
+            Ok(())
+        } else {
+            Err(deny(self.role, "manage tags"))
+        }
+    }
+
+    pub fn require_purge(&self) -> Result<(), ApiError> {
+        if self.role.can_purge() {
+            Ok(())
+        } else {
+            Err(deny(self.role, "permanently delete content"))
+        }
+    }
+
+    pub fn require_security_log(&self) -> Result<(), ApiError> {
+        if self.role.can_view_security_log() {
+            Ok(())
+        } else {
+            Err(deny(self.role, "view login activity"))
+        }
+    }
+
+    /// Editors and admins may change anything; authors only rows in `table` (`articles` or
+    /// `books`) they wrote. A missing row passes so the handler can answer 404 itself.
+    pub async fn require_edit(&self, pool: &MySqlPool, table: &str, id: &[u8]) -> Result<(), ApiError> {
+        if self.role.can_edit_any_content() {
+            return Ok(());
+        }
+        if self.role != Role::Author {
+            return Err(deny(self.role, "edit content"));
+        }
+
+        let author_id: Option<Option<Vec<u8>>> =
+            sqlx::query_scalar(&format!("SELECT author_id FROM {} WHERE id = ?", table))
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
+        match author_id {
+            None => Ok(()),
+            Some(Some(author_id)) if author_id == self.id => Ok(()),
+            Some(_) => Err(ApiError::forbidden("Authors may only edit their own content")),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use axum::http::Method;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+use crate::models::api_token::{ApiToken, TokenScope};
+use crate::models::user::User;
+
+/// Marks the string as one of ours, so leaked tokens are easy to grep for.
+pub const TOKEN_PREFIX: &str = "mhp_";
+const TOKEN_BYTES: usize = 32;
+/// Characters of the token kept in clear to tell tokens apart in the list.
+const DISPLAY_PREFIX_LENGTH: usize = 12;
+/// `last_used_at` is written at most this often per token.
+const TOUCH_INTERVAL_SECS: i64 = 60;
+pub const MAX_TOKEN_DAYS: i64 = 365;
+
+const TOKEN_COLUMNS: &str = "id, user_id, name, token_prefix, scopes, last_used_at, expires_at, created_at";
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// The scope a token needs for an admin route, given its path below `/admin`. Routes that
+/// return `None` (sessions, 2FA, tokens, trash, security log) stay session-only.
+pub fn required_scope(method: &Method, path: &str) -> Option<TokenScope> {
+    let read = method == Method::GET || method == Method::HEAD;
+    let section = path.trim_start_matches('/').split('/').next().unwrap_or("");
+    match (section, read) {
+        ("articles", true) | ("tags", true) | ("search", true) => Some(TokenScope::ArticlesRead),
+        ("articles", false) => Some(TokenScope::ArticlesWrite),
+        ("books", true) => Some(TokenScope::BooksRead),
+        ("books", false) => Some(TokenScope::BooksWrite),
+        ("tags", false) => Some(TokenScope::TagsWrite),
+        ("upload-image", false) => Some(TokenScope::Upload),
+        _ => None,
+    }
+}
+
+/// Store a new token and return its id and the token itself, which cannot be shown again.
+pub async fn create_api_token(
+    pool: &MySqlPool,
+    user_id: &[u8],
+    name: &str,
+    scopes: &[TokenScope],
+    expires_at: Option<OffsetDateTime>,
+) -> Result<(Uuid, String), sqlx::Error> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = format!("{}{}", TOKEN_PREFIX, hex::encode(bytes));
+    let scopes = scopes.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(",");
+
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO api_tokens (id, user_id, name, token_hash, token_prefix, scopes, expires_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(id.as_bytes().to_vec())
+    .bind(user_id)
+    .bind(name)
+    .bind(hash_token(&token))
+    .bind(&token[..DISPLAY_PREFIX_LENGTH])
+    .bind(scopes)
+    .bind(expires_at)
+    .execute(pool)
+    .await?;
+    Ok((id, token))
+}
+
+/// The unexpired token matching a bearer value, and its owner.
+pub async fn find_api_token(pool: &MySqlPool, token: &str) -> Option<(ApiToken, User)> {
+    if !token.starts_with(TOKEN_PREFIX) {
+        return None;
+    }
+    let api_token = sqlx::query_as::<_, ApiToken>(&format!(
+        "SELECT {} FROM api_tokens WHERE token_hash = ? AND (expires_at IS NULL OR expires_at > ?)",
+        TOKEN_COLUMNS
+    ))
+    .bind(hash_token(token))
+    .bind(OffsetDateTime::now_utc())
+    .fetch_optional(pool)
+    .await
+    .ok()??;
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, email, password_hash, role, created_at FROM users WHERE id = ?"
+    )
+    .bind(&api_token.user_id)
+    .fetch_optional(pool)
+    .await
+    .ok()??;
+    Some((api_token, user))
+}
+
+pub async fn touch_api_token(pool: &MySqlPool, token: &ApiToken) -> Result<(), sqlx::Error> {
+    let now = OffsetDateTime::now_utc();
+    if token
+        .last_used_at
+        .is_some_and(|used| (now - used).whole_seconds() < TOUCH_INTERVAL_SECS)
+    {
+        return Ok(());
+    }
+    sqlx::query("UPDATE api_tokens SET last_used_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(&token.id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// All tokens of a user, including expired ones, newest first.
+pub async fn list_api_tokens(pool: &MySqlPool, user_id: &[u8]) -> Result<Vec<ApiToken>, sqlx::Error> {
+    sqlx::query_as::<_, ApiToken>(&format!(
+        "SELECT {} FROM api_tokens WHERE user_id = ? ORDER BY created_at DESC",
+        TOKEN_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Revoke one of the user's tokens. Returns whether it existed.
+pub async fn delete_api_token(pool: &MySqlPool, user_id: &[u8], token_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM api_tokens WHERE id = ? AND user_id = ?")
+        .bind(token_id.as_bytes().to_vec())
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Revoke every token of a user. Returns the number removed.
+pub async fn delete_user_api_tokens(pool: &MySqlPool, user_id: &[u8]) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM api_tokens WHERE user_id = ?")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,107 @@
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use time::Duration;
+use tower_cookies::Cookies;
+use crate::auth::session::{auth_cookie, SESSION_COOKIE};
+use crate::config::Config;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const CSRF_HEADER: &str = "x-csrf-token";
+/// Random value that anonymous tokens (for `/login`) are bound to until a session exists.
+pub const CSRF_SEED_COOKIE: &str = "csrf_seed";
+const CSRF_SEED_DAYS: i64 = 1;
+
+fn signature(secret: &str, binding: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(b"csrf:");
+    mac.update(binding.as_bytes());
+    mac
+}
+
+/// What the token is bound to: the session cookie once signed in, otherwise the seed.
+/// A seed-bound token stops working as soon as a session exists.
+fn binding(cookies: &Cookies) -> Option<String> {
+    [SESSION_COOKIE, CSRF_SEED_COOKIE].into_iter().find_map(|name| {
+        cookies
+            .get(name)
+            .filter(|c| !c.value().is_empty())
+            .map(|c| format!("{}:{}", name, c.value()))
+    })
+}
+
+fn sign(secret: &str, binding: &str) -> String {
+    hex::encode(signature(secret, binding).finalize().into_bytes())
+}
+
+fn verify(secret: &str, cookies: &Cookies, token: &str) -> bool {
+    let (Some(binding), Ok(token)) = (binding(cookies), hex::decode(token)) else {
+        return false;
+    };
+    signature(secret, &binding).verify_slice(&token).is_ok()
+}
+
+/// The token for this browser: an HMAC of the session id, or of a fresh seed cookie when
+/// there is no session yet. Nothing is stored server-side; the token changes with the session.
+pub fn issue_token(config: &Config, cookies: &Cookies) -> String {
+    let binding = match binding(cookies) {
+        Some(binding) => binding,
+        None => {
+            let mut seed = [0u8; 16];
+            rand::thread_rng().fill_bytes(&mut seed);
+            let seed = hex::encode(seed);
+            cookies.add(auth_cookie(config, CSRF_SEED_COOKIE, seed.clone(), Duration::days(CSRF_SEED_DAYS)));
+            format!("{}:{}", CSRF_SEED_COOKIE, seed)
+        }
+    };
+    sign(&config.session_secret, &binding)
+}
+
+/// Whether `token` was issued for this request's session, or for its seed when it has none.
+pub fn verify_token(config: &Config, cookies: &Cookies, token: &str) -> bool {
+    verify(&config.session_secret, cookies, token)
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_cookies::Cookie;
+    use super::*;
+
+    const SECRET: &str = "test-secret";
+
+    fn jar(entries: &[(&'static str, &'static str)]) -> Cookies {
+        let cookies = Cookies::default();
+        for (name, value) in entries {
+            cookies.add(Cookie::new(*name, *value));
+        }
+        cookies
+    }
+
+    #[test]
+    fn session_token_round_trips() {
+        let cookies = jar(&[(SESSION_COOKIE, "abc")]);
+        let token = sign(SECRET, &binding(&cookies).unwrap());
+        assert!(verify(SECRET, &cookies, &token));
+    }
+
+    #[test]
+    fn rejects_other_session_secret_or_garbage() {
+        let cookies = jar(&[(SESSION_COOKIE, "abc")]);
+        let token = sign(SECRET, &binding(&cookies).unwrap());
+        assert!(!verify(SECRET, &jar(&[(SESSION_COOKIE, "abd")]), &token));
+        assert!(!verify("other-secret", &cookies, &token));
+        assert!(!verify(SECRET, &cookies, "not-hex"));
+        assert!(!verify(SECRET, &jar(&[]), &token));
+    }
+
+    #[test]
+    fn seed_token_stops_working_once_signed_in() {
+        let anonymous = jar(&[(CSRF_SEED_COOKIE, "seed")]);
+        let token = sign(SECRET, &binding(&anonymous).unwrap());
+        assert!(verify(SECRET, &anonymous, &token));
+
+        let signed_in = jar(&[(CSRF_SEED_COOKIE, "seed"), (SESSION_COOKIE, "abc")]);
+        assert!(!verify(SECRET, &signed_in, &token));
+    }
+}
@@ -1 +1,9 @@
+pub mod access;
+pub mod api_tokens;
+pub mod csrf;
+pub mod password_reset;
+pub mod preview;
 pub mod session;
+pub mod throttle;
+pub mod two_factor;
+pub mod users;
@@ -0,0 +1,58 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// How long a reset link stays usable.
+pub const RESET_TOKEN_MINUTES: i64 = 60;
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Issue a reset token for the user, voiding any earlier unused ones. The token is only
+/// ever sent by mail.
+pub async fn create_reset_token(pool: &MySqlPool, user_id: &[u8]) -> Result<String, sqlx::Error> {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+    let expires_at = OffsetDateTime::now_utc() + time::Duration::minutes(RESET_TOKEN_MINUTES);
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = ? AND used_at IS NULL")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("INSERT INTO password_reset_tokens (id, user_id, token_hash, expires_at) VALUES (?, ?, ?, ?)")
+        .bind(Uuid::new_v4().as_bytes().to_vec())
+        .bind(user_id)
+        .bind(hash_token(&token))
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(token)
+}
+
+/// Mark an unexpired, unused token as used and return its user. A token works only once,
+/// even when two requests race with it.
+pub async fn consume_reset_token(pool: &MySqlPool, token: &str) -> Result<Option<Vec<u8>>, sqlx::Error> {
+    let token_hash = hash_token(token.trim());
+    let now = OffsetDateTime::now_utc();
+    let result = sqlx::query(
+        "UPDATE password_reset_tokens SET used_at = ? WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?"
+    )
+    .bind(now)
+    .bind(&token_hash)
+    .bind(now)
+    .execute(pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+    sqlx::query_scalar("SELECT user_id FROM password_reset_tokens WHERE token_hash = ?")
+        .bind(&token_hash)
+        .fetch_optional(pool)
+        .await
+}
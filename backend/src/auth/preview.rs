@@ -0,0 +1,126 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::MySqlPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+use crate::models::preview_token::PreviewToken;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const DEFAULT_PREVIEW_HOURS: i64 = 72;
+pub const MAX_PREVIEW_HOURS: i64 = 24 * 30;
+
+fn signature(secret: &str, payload: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// Sign a preview token as `<token id>.<expiry unix seconds>.<hex HMAC-SHA256>`.
+pub fn sign_token(secret: &str, token_id: Uuid, expires_at: OffsetDateTime) -> String {
+    let payload = format!("{}.{}", token_id.simple(), expires_at.unix_timestamp());
+    let sig = hex::encode(signature(secret, &payload).finalize().into_bytes());
+    format!("{}.{}", payload, sig)
+}
+
+/// Check the signature and expiry of a token string, returning its token id.
+/// Revocation is checked separately against the database.
+pub fn verify_token(secret: &str, token: &str) -> Option<Uuid> {
+    let (payload, sig) = token.rsplit_once('.')?;
+    let (token_id, expires) = payload.split_once('.')?;
+    let sig = hex::decode(sig).ok()?;
+    signature(secret, payload).verify_slice(&sig).ok()?;
+
+    let expires_at = OffsetDateTime::from_unix_timestamp(expires.parse().ok()?).ok()?;
+    if expires_at <= OffsetDateTime::now_utc() {
+        return None;
+    }
+    Uuid::parse_str(token_id).ok()
+}
+
+pub async fn create_preview_token(
+    pool: &MySqlPool,
+    article_id: &[u8],
+    expires_at: OffsetDateTime,
+    created_by: Option<Uuid>,
+) -> Result<Uuid, sqlx::Error> {
+    let token_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO preview_tokens (id, article_id, expires_at, created_by) VALUES (?, ?, ?, ?)")
+        .bind(token_id.as_bytes().to_vec())
+        .bind(article_id)
+        .bind(expires_at)
+        .bind(created_by.map(|u| u.as_bytes().to_vec()))
+        .execute(pool)
+        .await?;
+    Ok(token_id)
+}
+
+/// Look up a token that is neither revoked nor expired.
+pub async fn get_active_preview_token(pool: &MySqlPool, token_id: Uuid) -> Result<Option<PreviewToken>, sqlx::Error> {
+    sqlx::query_as::<_, PreviewToken>(
+        "SELECT id, article_id, expires_at, revoked_at, created_by, created_at FROM preview_tokens \
+         WHERE id = ? AND revoked_at IS NULL AND expires_at > ?"
+    )
+    .bind(token_id.as_bytes().to_vec())
+    .bind(OffsetDateTime::now_utc())
+    .fetch_optional(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+    use super::*;
+
+    const SECRET: &str = "test-secret";
+
+    fn in_one_hour() -> OffsetDateTime {
+        OffsetDateTime::now_utc() + Duration::hours(1)
+    }
+
+    #[test]
+    fn round_trips() {
+        let id = Uuid::new_v4();
+        let token = sign_token(SECRET, id, in_one_hour());
+        assert_eq!(verify_token(SECRET, &token), Some(id));
+    }
+
+    #[test]
+    fn rejects_other_secret() {
+        let token = sign_token(SECRET, Uuid::new_v4(), in_one_hour());
+        assert_eq!(verify_token("other-secret", &token), None);
+    }
+
+    #[test]
+    fn rejects_tampered_parts() {
+        let id = Uuid::new_v4();
+        let expires_at = in_one_hour();
+        let token = sign_token(SECRET, id, expires_at);
+        let (payload, sig) = token.rsplit_once('.').unwrap();
+
+        // Another token id, a later expiry, or a flipped signature digit
+        let other_id = token.replacen(&id.simple().to_string(), &Uuid::new_v4().simple().to_string(), 1);
+        assert_eq!(verify_token(SECRET, &other_id), None);
+        let extended = token.replacen(
+            &expires_at.unix_timestamp().to_string(),
+            &(expires_at + Duration::days(30)).unix_timestamp().to_string(),
+            1,
+        );
+        assert_eq!(verify_token(SECRET, &extended), None);
+        let flipped = if sig.starts_with('0') { '1' } else { '0' };
+        assert_eq!(verify_token(SECRET, &format!("{}.{}{}", payload, flipped, &sig[1..])), None);
+    }
+
+    #[test]
+    fn rejects_expired() {
+        let token = sign_token(SECRET, Uuid::new_v4(), OffsetDateTime::now_utc() - Duration::seconds(1));
+        assert_eq!(verify_token(SECRET, &token), None);
+    }
+
+    #[test]
+    fn rejects_malformed() {
+        for token in ["", "abc", "a.b", "a.b.c", "not-a-uuid.99999999999.00"] {
+            assert_eq!(verify_token(SECRET, token), None, "{:?}", token);
+        }
+    }
+}
@@ -1,49 +1,158 @@
 use sqlx::MySqlPool;
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
+use tower_cookies::{Cookie, Cookies};
 use uuid::Uuid;
-use crate::config::SESSION_DURATION_DAYS;
+use crate::config::Config;
+use crate::models::session::Session;
 use crate::models::user::User;
 
-pub async fn create_session(pool: &MySqlPool, user_id: Uuid) -> Result<Uuid, sqlx::Error> {
+pub const SESSION_COOKIE: &str = "session_id";
+/// `last_seen_at` and the sliding expiry are written at most this often per session.
+const TOUCH_INTERVAL_SECS: i64 = 60;
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+const SESSION_COLUMNS: &str = "id, user_id, user_agent, ip_address, last_seen_at, expires_at, created_at";
+
+pub fn auth_cookie(config: &Config, name: &'static str, value: String, max_age: Duration) -> Cookie<'static> {
+    let mut cookie = Cookie::new(name, value);
+    cookie.set_http_only(true);
+    cookie.set_same_site(tower_cookies::cookie::SameSite::Lax);
+    cookie.set_path("/");
+    cookie.set_max_age(max_age);
+    if config.is_production {
+        cookie.set_secure(true);
+    }
+    cookie
+}
+
+pub fn clear_cookie(cookies: &Cookies, name: &'static str) {
+    let mut removal = Cookie::new(name, "");
+    removal.set_path("/");
+    removal.set_max_age(Duration::seconds(0));
+    cookies.add(removal);
+}
+
+/// When a session last used at `now` expires: the idle window, capped by the absolute
+/// maximum counted from its creation.
+fn sliding_expiry(config: &Config, created_at: OffsetDateTime, now: OffsetDateTime) -> OffsetDateTime {
+    let idle = now + Duration::days(config.session_idle_days);
+    let absolute = created_at + Duration::days(config.session_max_days);
+    idle.min(absolute)
+}
+
+pub async fn create_session(
+    pool: &MySqlPool,
+    config: &Config,
+    user_id: Uuid,
+    user_agent: Option<&str>,
+    ip_address: &str,
+) -> Result<(Uuid, OffsetDateTime), sqlx::Error> {
     let session_id = Uuid::new_v4();
-    let expires_at = OffsetDateTime::now_utc() + time::Duration::days(SESSION_DURATION_DAYS);
-    let session_id_bytes = session_id.as_bytes().to_vec();
-    let user_id_bytes = user_id.as_bytes().to_vec();
-    sqlx::query("INSERT INTO sessions (id, user_id, expires_at) VALUES (?, ?, ?)")
-        .bind(session_id_bytes)
-        .bind(user_id_bytes)
-        .bind(expires_at)
-        .execute(pool)
-        .await?;
-    Ok(session_id)
+    let now = OffsetDateTime::now_utc();
+    let expires_at = sliding_expiry(config, now, now);
+    let user_agent = user_agent.map(|ua| ua.chars().take(MAX_USER_AGENT_LENGTH).collect::<String>());
+    sqlx::query(
+        "INSERT INTO sessions (id, user_id, user_agent, ip_address, last_seen_at, expires_at, created_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(session_id.as_bytes().to_vec())
+    .bind(user_id.as_bytes().to_vec())
+    .bind(user_agent)
+    .bind(ip_address)
+    .bind(now)
+    .bind(expires_at)
+    .bind(now)
+    .execute(pool)
+    .await?;
+    Ok((session_id, expires_at))
 }
 
-pub async fn get_user_by_session(pool: &MySqlPool, session_id: &str) -> Option<User> {
+/// The session behind a cookie value and its user, if the session has not expired.
+pub async fn find_session(pool: &MySqlPool, session_id: &str) -> Option<(Session, User)> {
     let session_uuid = Uuid::parse_str(session_id).ok()?;
     let session_id_bytes = session_uuid.as_bytes().to_vec();
     let now = OffsetDateTime::now_utc();
 
-    // Fetch user only for a valid, non-expired session
-    let row = sqlx::query_as::<_, User>(
-        "SELECT u.id, u.email, u.password_hash, u.created_at FROM users u \
-         JOIN sessions s ON u.id = s.user_id WHERE s.id = ? AND s.expires_at > ?"
-    )
-    .bind(session_id_bytes.clone())
+    let session = sqlx::query_as::<_, Session>(&format!(
+        "SELECT {} FROM sessions WHERE id = ? AND expires_at > ?",
+        SESSION_COLUMNS
+    ))
+    .bind(&session_id_bytes)
     .bind(now)
     .fetch_optional(pool)
     .await
     .ok()?;
 
-    if row.is_none() {
+    let Some(session) = session else {
         // Session not found or expired: purge any expired record for this ID
         let _ = sqlx::query("DELETE FROM sessions WHERE id = ? AND expires_at <= ?")
             .bind(session_id_bytes)
             .bind(now)
             .execute(pool)
             .await;
+        return None;
+    };
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, email, password_hash, role, created_at FROM users WHERE id = ?"
+    )
+    .bind(&session.user_id)
+    .fetch_optional(pool)
+    .await
+    .ok()??;
+    Some((session, user))
+}
+
+/// Record activity on a session and slide its expiry forward. Returns the new expiry when
+/// it was written, so the caller can renew the cookie to match.
+pub async fn touch_session(
+    pool: &MySqlPool,
+    config: &Config,
+    session: &Session,
+) -> Result<Option<OffsetDateTime>, sqlx::Error> {
+    let now = OffsetDateTime::now_utc();
+    let recently_seen = session
+        .last_seen_at
+        .is_some_and(|seen| (now - seen).whole_seconds() < TOUCH_INTERVAL_SECS);
+    if recently_seen {
+        return Ok(None);
     }
 
-    row
+    let expires_at = sliding_expiry(config, session.created_at.unwrap_or(now), now);
+    sqlx::query("UPDATE sessions SET last_seen_at = ?, expires_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(expires_at)
+        .bind(&session.id)
+        .execute(pool)
+        .await?;
+    Ok(Some(expires_at))
+}
+
+/// Unexpired sessions of a user, most recently used first.
+pub async fn list_user_sessions(pool: &MySqlPool, user_id: &[u8]) -> Result<Vec<Session>, sqlx::Error> {
+    sqlx::query_as::<_, Session>(&format!(
+        "SELECT {} FROM sessions WHERE user_id = ? AND expires_at > ? \
+         ORDER BY COALESCE(last_seen_at, created_at) DESC",
+        SESSION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(OffsetDateTime::now_utc())
+    .fetch_all(pool)
+    .await
+}
+
+/// Delete one of the user's sessions by its public id. Returns whether one was found.
+pub async fn delete_user_session(pool: &MySqlPool, user_id: &[u8], public_id: &str) -> Result<bool, sqlx::Error> {
+    let sessions = list_user_sessions(pool, user_id).await?;
+    let Some(session) = sessions.into_iter().find(|s| s.public_id() == public_id) else {
+        return Ok(false);
+    };
+    let result = sqlx::query("DELETE FROM sessions WHERE id = ? AND user_id = ?")
+        .bind(&session.id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
 }
 
 pub async fn delete_session(pool: &MySqlPool, session_id: &str) -> Result<(), sqlx::Error> {
@@ -56,3 +165,31 @@ pub async fn delete_session(pool: &MySqlPool, session_id: &str) -> Result<(), sq
     }
     Ok(())
 }
+
+/// Log a user out everywhere. Returns the number of sessions removed.
+pub async fn delete_user_sessions(pool: &MySqlPool, user_id: &[u8]) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM sessions WHERE user_id = ?")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// Log a user out everywhere except the session with id `keep`. Returns the number removed.
+pub async fn delete_other_sessions(pool: &MySqlPool, user_id: &[u8], keep: &[u8]) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM sessions WHERE user_id = ? AND id <> ?")
+        .bind(user_id)
+        .bind(keep)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// Remove every expired session. Returns the number of sessions removed.
+pub async fn purge_expired_sessions(pool: &MySqlPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= ?")
+        .bind(OffsetDateTime::now_utc())
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
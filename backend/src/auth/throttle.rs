@@ -0,0 +1,249 @@
+use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroU32;
+use axum::http::HeaderMap;
+use governor::clock::{Clock, DefaultClock};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use sqlx::MySqlPool;
+use time::OffsetDateTime;
+use crate::AppState;
+use crate::error::ApiError;
+use crate::models::login_attempt::{LoginAttempt, LoginLockout};
+
+/// Login requests allowed per minute from one IP / against one email, before any
+/// failures are counted.
+const IP_REQUESTS_PER_MINUTE: u32 = 20;
+const EMAIL_REQUESTS_PER_MINUTE: u32 = 10;
+
+/// Consecutive failures before a lockout starts. IPs get more room since several people
+/// can share one address.
+const EMAIL_FAILURE_THRESHOLD: i32 = 5;
+const IP_FAILURE_THRESHOLD: i32 = 20;
+/// The first lockout lasts this long and doubles with every further failure.
+const BASE_LOCKOUT_SECS: i64 = 60;
+const MAX_LOCKOUT_SECS: i64 = 24 * 60 * 60;
+/// A failure streak starts over after a day without failures.
+const FAILURE_WINDOW_HOURS: i64 = 24;
+/// How long `login_attempts` rows are kept for the login log.
+pub const LOGIN_ATTEMPT_RETENTION_DAYS: i64 = 90;
+/// Keyed limiters forget idle keys once they hold this many.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+enum Scope {
+    Email,
+    Ip,
+}
+
+impl Scope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Scope::Email => "email",
+            Scope::Ip => "ip",
+        }
+    }
+
+    fn threshold(self) -> i32 {
+        match self {
+            Scope::Email => EMAIL_FAILURE_THRESHOLD,
+            Scope::Ip => IP_FAILURE_THRESHOLD,
+        }
+    }
+}
+
+/// Brute-force protection for `POST /login`: in-memory request rate limits plus
+/// exponential lockouts kept in the database so they survive restarts.
+pub struct LoginThrottle {
+    by_ip: DefaultKeyedRateLimiter<String>,
+    by_email: DefaultKeyedRateLimiter<String>,
+}
+
+impl Default for LoginThrottle {
+    fn default() -> Self {
+        let per_minute = |n: u32| Quota::per_minute(NonZeroU32::new(n).expect("quota is non-zero"));
+        LoginThrottle {
+            by_ip: RateLimiter::keyed(per_minute(IP_REQUESTS_PER_MINUTE)),
+            by_email: RateLimiter::keyed(per_minute(EMAIL_REQUESTS_PER_MINUTE)),
+        }
+    }
+}
+
+impl LoginThrottle {
+    /// Seconds the caller must wait if either the IP or the email is over its request rate.
+    pub fn check_rate(&self, ip: &str, email: &str) -> Option<u64> {
+        let now = DefaultClock::default().now();
+        for (limiter, key) in [(&self.by_ip, ip), (&self.by_email, email)] {
+            if limiter.len() > MAX_TRACKED_KEYS {
+                limiter.retain_recent();
+            }
+            if let Err(not_until) = limiter.check_key(&key.to_string()) {
+                return Some(not_until.wait_time_from(now).as_secs().max(1));
+            }
+        }
+        None
+    }
+}
+
+/// The client address, taken from the first `X-Forwarded-For` hop when the backend sits
+/// behind a trusted proxy.
+pub fn client_ip(headers: &HeaderMap, peer: SocketAddr, trust_proxy_headers: bool) -> String {
+    if trust_proxy_headers {
+        let forwarded = headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .and_then(|v| v.trim().parse::<IpAddr>().ok());
+        if let Some(ip) = forwarded {
+            return ip.to_string();
+        }
+    }
+    peer.ip().to_string()
+}
+
+/// Seconds left on the longest active lockout for this email or IP.
+pub async fn active_lockout(pool: &MySqlPool, ip: &str, email: &str) -> Result<Option<u64>, sqlx::Error> {
+    let locked_until: Option<OffsetDateTime> = sqlx::query_scalar(
+        "SELECT MAX(locked_until) FROM login_lockouts \
+         WHERE (scope = 'email' AND lock_key = ?) OR (scope = 'ip' AND lock_key = ?)"
+    )
+    .bind(email)
+    .bind(ip)
+    .fetch_one(pool)
+    .await?;
+
+    let remaining = locked_until.map(|until| (until - OffsetDateTime::now_utc()).whole_seconds());
+    Ok(remaining.filter(|secs| *secs > 0).map(|secs| secs as u64))
+}
+
+/// Answer 429 while the IP or email is over its request rate or locked out. Used by `/login`
+/// and by every password re-check a signed-in user can make, so a stolen session cookie
+/// gets no more guesses than the login form.
+pub async fn ensure_not_throttled(state: &AppState, ip: &str, email: &str) -> Result<(), ApiError> {
+    if let Some(retry_after) = state.login_throttle.check_rate(ip, email) {
+        return Err(ApiError::TooManyRequests(retry_after));
+    }
+    if let Some(retry_after) = active_lockout(&state.pool, ip, email).await? {
+        return Err(ApiError::TooManyRequests(retry_after));
+    }
+    Ok(())
+}
+
+async fn bump_failures(pool: &MySqlPool, scope: Scope, key: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO login_lockouts (scope, lock_key, failures) VALUES (?, ?, 1) \
+         ON DUPLICATE KEY UPDATE failures = IF(updated_at < NOW() - INTERVAL ? HOUR, 1, failures + 1)"
+    )
+    .bind(scope.as_str())
+    .bind(key)
+    .bind(FAILURE_WINDOW_HOURS)
+    .execute(pool)
+    .await?;
+
+    let failures: i32 = sqlx::query_scalar("SELECT failures FROM login_lockouts WHERE scope = ? AND lock_key = ?")
+        .bind(scope.as_str())
+        .bind(key)
+        .fetch_one(pool)
+        .await?;
+    if failures < scope.threshold() {
+        return Ok(());
+    }
+
+    let doublings = (failures - scope.threshold()).min(20) as u32;
+    let secs = (BASE_LOCKOUT_SECS << doublings).min(MAX_LOCKOUT_SECS);
+    sqlx::query("UPDATE login_lockouts SET locked_until = ? WHERE scope = ? AND lock_key = ?")
+        .bind(OffsetDateTime::now_utc() + time::Duration::seconds(secs))
+        .bind(scope.as_str())
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Log the attempt and, on failure, extend the lockouts. A success only clears the email's
+/// lockout: the IP lockout runs out on its own, so logging into an account of one's own
+/// does not reopen the address for spraying others.
+pub async fn record_login_attempt(pool: &MySqlPool, ip: &str, email: &str, succeeded: bool) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO login_attempts (email, ip_address, succeeded) VALUES (?, ?, ?)")
+        .bind(email)
+        .bind(ip)
+        .bind(succeeded)
+        .execute(pool)
+        .await?;
+
+    if succeeded {
+        sqlx::query("DELETE FROM login_lockouts WHERE scope = 'email' AND lock_key = ?")
+            .bind(email)
+            .execute(pool)
+            .await?;
+    } else {
+        bump_failures(pool, Scope::Email, email).await?;
+        bump_failures(pool, Scope::Ip, ip).await?;
+    }
+    Ok(())
+}
+
+/// Drop login attempts past the retention period and lockouts that have both run out and
+/// fallen outside the failure window. Returns the number of attempts removed.
+pub async fn purge_login_history(pool: &MySqlPool) -> Result<u64, sqlx::Error> {
+    let purged = sqlx::query("DELETE FROM login_attempts WHERE created_at < NOW() - INTERVAL ? DAY")
+        .bind(LOGIN_ATTEMPT_RETENTION_DAYS)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    sqlx::query(
+        "DELETE FROM login_lockouts WHERE (locked_until IS NULL OR locked_until < NOW()) \
+         AND updated_at < NOW() - INTERVAL ? HOUR"
+    )
+    .bind(FAILURE_WINDOW_HOURS)
+    .execute(pool)
+    .await?;
+    Ok(purged)
+}
+
+/// Background loop that keeps the login log and lockout table from growing without bound.
+pub async fn run_login_history_worker(pool: MySqlPool, interval: std::time::Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match purge_login_history(&pool).await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Purged {} old login attempt(s)", n),
+            Err(e) => tracing::error!("Login history purge failed: {}", e),
+        }
+    }
+}
+
+pub async fn recent_login_attempts(
+    pool: &MySqlPool,
+    failed_only: bool,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<LoginAttempt>, sqlx::Error> {
+    sqlx::query_as::<_, LoginAttempt>(
+        "SELECT email, ip_address, succeeded, created_at FROM login_attempts \
+         WHERE (? = false OR succeeded = false) ORDER BY id DESC LIMIT ? OFFSET ?"
+    )
+    .bind(failed_only)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn active_lockouts(pool: &MySqlPool) -> Result<Vec<LoginLockout>, sqlx::Error> {
+    sqlx::query_as::<_, LoginLockout>(
+        "SELECT scope, lock_key, failures, locked_until FROM login_lockouts \
+         WHERE locked_until > NOW() ORDER BY locked_until DESC"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Lift the lockout on an email or IP before it runs out.
+pub async fn clear_lockout(pool: &MySqlPool, scope: &str, key: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM login_lockouts WHERE scope = ? AND lock_key = ?")
+        .bind(scope)
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,221 @@
+use data_encoding::BASE32_NOPAD;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, MySqlPool};
+use time::OffsetDateTime;
+use uuid::Uuid;
+use crate::utils::totp;
+
+/// How long the password-checked half of a login waits for the second factor.
+pub const PENDING_LOGIN_MINUTES: i64 = 5;
+/// Wrong codes allowed against one pending login before it is thrown away.
+pub const MAX_PENDING_ATTEMPTS: i32 = 5;
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+#[derive(Debug, FromRow)]
+pub struct TwoFactorState {
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<OffsetDateTime>,
+    pub totp_last_step: Option<i64>,
+}
+
+impl TwoFactorState {
+    pub fn is_enabled(&self) -> bool {
+        self.totp_enabled_at.is_some() && self.totp_secret.is_some()
+    }
+}
+
+pub async fn two_factor_state(pool: &MySqlPool, user_id: &[u8]) -> Result<TwoFactorState, sqlx::Error> {
+    sqlx::query_as::<_, TwoFactorState>(
+        "SELECT totp_secret, totp_enabled_at, totp_last_step FROM users WHERE id = ?"
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Store a new, not yet enabled secret for the user and return it.
+pub async fn start_enrollment(pool: &MySqlPool, user_id: &[u8]) -> Result<String, sqlx::Error> {
+    let secret = totp::generate_secret();
+    sqlx::query("UPDATE users SET totp_secret = ?, totp_enabled_at = NULL, totp_last_step = NULL WHERE id = ?")
+        .bind(&secret)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(secret)
+}
+
+/// Turn 2FA on after the first code was verified at `step`, returning fresh recovery codes.
+pub async fn enable_two_factor(pool: &MySqlPool, user_id: &[u8], step: i64) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query("UPDATE users SET totp_enabled_at = NOW(), totp_last_step = ? WHERE id = ?")
+        .bind(step)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    replace_recovery_codes(pool, user_id).await
+}
+
+pub async fn disable_two_factor(pool: &MySqlPool, user_id: &[u8]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE users SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL WHERE id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM recovery_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    hex::encode(Sha256::digest(normalize_recovery_code(code).as_bytes()))
+}
+
+/// Invalidate all recovery codes of the user and issue a new set. Only hashes are stored,
+/// so the returned codes cannot be shown again.
+pub async fn replace_recovery_codes(pool: &MySqlPool, user_id: &[u8]) -> Result<Vec<String>, sqlx::Error> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            rand::thread_rng().fill_bytes(&mut bytes);
+            let code = BASE32_NOPAD.encode(&bytes).to_lowercase();
+            format!("{}-{}", &code[..4], &code[4..])
+        })
+        .collect();
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM recovery_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    for code in &codes {
+        sqlx::query("INSERT INTO recovery_codes (id, user_id, code_hash) VALUES (?, ?, ?)")
+            .bind(Uuid::new_v4().as_bytes().to_vec())
+            .bind(user_id)
+            .bind(hash_recovery_code(code))
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(codes)
+}
+
+pub async fn remaining_recovery_codes(pool: &MySqlPool, user_id: &[u8]) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM recovery_codes WHERE user_id = ? AND used_at IS NULL")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Accept either a current TOTP code or an unused recovery code. Each TOTP step and each
+/// recovery code works only once.
+pub async fn verify_second_factor(
+    pool: &MySqlPool,
+    user_id: &[u8],
+    state: &TwoFactorState,
+    code: &str,
+) -> Result<bool, sqlx::Error> {
+    let Some(secret) = state.totp_secret.as_deref() else {
+        return Ok(false);
+    };
+
+    if let Some(step) = totp::verify(secret, code, OffsetDateTime::now_utc().unix_timestamp()) {
+        // Conditional update so two requests racing with the same code cannot both pass
+        let result = sqlx::query(
+            "UPDATE users SET totp_last_step = ? WHERE id = ? AND (totp_last_step IS NULL OR totp_last_step < ?)"
+        )
+        .bind(step)
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await?;
+        return Ok(result.rows_affected() > 0);
+    }
+
+    let result = sqlx::query(
+        "UPDATE recovery_codes SET used_at = NOW() WHERE user_id = ? AND code_hash = ? AND used_at IS NULL LIMIT 1"
+    )
+    .bind(user_id)
+    .bind(hash_recovery_code(code))
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn create_pending_login(pool: &MySqlPool, user_id: &[u8]) -> Result<Uuid, sqlx::Error> {
+    let id = Uuid::new_v4();
+    let expires_at = OffsetDateTime::now_utc() + time::Duration::minutes(PENDING_LOGIN_MINUTES);
+    sqlx::query("INSERT INTO pending_logins (id, user_id, expires_at) VALUES (?, ?, ?)")
+        .bind(id.as_bytes().to_vec())
+        .bind(user_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+    Ok(id)
+}
+
+/// The user waiting on an unexpired pending login, if any.
+pub async fn find_pending_login(pool: &MySqlPool, pending_id: &str) -> Result<Option<Vec<u8>>, sqlx::Error> {
+    let Ok(id) = Uuid::parse_str(pending_id) else {
+        return Ok(None);
+    };
+    sqlx::query_scalar("SELECT user_id FROM pending_logins WHERE id = ? AND expires_at > ?")
+        .bind(id.as_bytes().to_vec())
+        .bind(OffsetDateTime::now_utc())
+        .fetch_optional(pool)
+        .await
+}
+
+/// Count a wrong code; the pending login is dropped once it runs out of attempts.
+/// Returns whether it is still usable.
+pub async fn record_failed_attempt(pool: &MySqlPool, pending_id: &str) -> Result<bool, sqlx::Error> {
+    let id = Uuid::parse_str(pending_id).map(|u| u.as_bytes().to_vec()).unwrap_or_default();
+    sqlx::query("UPDATE pending_logins SET attempts = attempts + 1 WHERE id = ?")
+        .bind(&id)
+        .execute(pool)
+        .await?;
+    let deleted = sqlx::query("DELETE FROM pending_logins WHERE id = ? AND attempts >= ?")
+        .bind(&id)
+        .bind(MAX_PENDING_ATTEMPTS)
+        .execute(pool)
+        .await?;
+    Ok(deleted.rows_affected() == 0)
+}
+
+pub async fn delete_pending_login(pool: &MySqlPool, pending_id: &str) -> Result<(), sqlx::Error> {
+    if let Ok(id) = Uuid::parse_str(pending_id) {
+        sqlx::query("DELETE FROM pending_logins WHERE id = ? OR expires_at <= ?")
+            .bind(id.as_bytes().to_vec())
+            .bind(OffsetDateTime::now_utc())
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovery_codes_ignore_case_and_separators() {
+        assert_eq!(normalize_recovery_code(" ABCD-efgh "), "abcdefgh");
+        assert_eq!(hash_recovery_code("abcd-efgh"), hash_recovery_code("ABCD EFGH"));
+        assert_ne!(hash_recovery_code("abcd-efgh"), hash_recovery_code("abcd-efgi"));
+    }
+
+    #[test]
+    fn recovery_code_hash_is_sha256_hex() {
+        let hash = hash_recovery_code("abcd-efgh");
+        assert_eq!(hash, hex::encode(Sha256::digest(b"abcdefgh")));
+        assert_eq!(hash.len(), 64);
+    }
+}
@@ -0,0 +1,52 @@
+use sqlx::MySqlPool;
+use uuid::Uuid;
+use crate::models::user::{Role, User};
+
+pub async fn find_user_by_email(pool: &MySqlPool, email: &str) -> Result<Option<User>, sqlx::Error> {
+    sqlx::query_as::<_, User>("SELECT id, email, password_hash, role, created_at FROM users WHERE email = ?")
+        .bind(email)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn list_users(pool: &MySqlPool) -> Result<Vec<User>, sqlx::Error> {
+    sqlx::query_as::<_, User>("SELECT id, email, password_hash, role, created_at FROM users ORDER BY created_at, email")
+        .fetch_all(pool)
+        .await
+}
+
+/// Insert a user with an already hashed password.
+pub async fn create_user(
+    pool: &MySqlPool,
+    email: &str,
+    password_hash: &str,
+    role: Role,
+) -> Result<Uuid, sqlx::Error> {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO users (id, email, password_hash, role) VALUES (?, ?, ?, ?)")
+        .bind(id.as_bytes().to_vec())
+        .bind(email)
+        .bind(password_hash)
+        .bind(role.as_str())
+        .execute(pool)
+        .await?;
+    Ok(id)
+}
+
+pub async fn set_password_hash(pool: &MySqlPool, user_id: &[u8], password_hash: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
+        .bind(password_hash)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn set_role(pool: &MySqlPool, user_id: &[u8], role: Role) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE users SET role = ? WHERE id = ?")
+        .bind(role.as_str())
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
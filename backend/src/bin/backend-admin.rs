@@ -0,0 +1,176 @@
+use std::io::{self, BufRead};
+use anyhow::{bail, Context};
+use clap::{Parser, Subcommand};
+use time::format_description::well_known::Rfc3339;
+use backend::auth::api_tokens::delete_user_api_tokens;
+use backend::auth::session::{delete_user_sessions, purge_expired_sessions};
+use backend::auth::throttle::purge_login_history;
+use backend::auth::two_factor::disable_two_factor;
+use backend::auth::users::{create_user, find_user_by_email, list_users, set_password_hash, set_role};
+use backend::config::Config;
+use backend::db;
+use backend::models::user::{Role, User};
+use backend::utils::password::hash_password;
+use backend::validation::validate_password;
+
+/// User, session and migration maintenance for the backend database.
+#[derive(Parser)]
+#[command(name = "backend-admin")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a user; prompts for the password unless --password-stdin is given.
+    CreateUser {
+        email: String,
+        /// One of admin, editor, author or viewer.
+        #[arg(long, default_value = "admin")]
+        role: Role,
+        /// Read the password from the first line of stdin.
+        #[arg(long)]
+        password_stdin: bool,
+    },
+    /// Set a new password for a user, log them out everywhere and revoke their API tokens.
+    ResetPassword {
+        email: String,
+        /// Read the password from the first line of stdin.
+        #[arg(long)]
+        password_stdin: bool,
+    },
+    /// List all users.
+    ListUsers,
+    /// Change a user's role (admin, editor, author or viewer).
+    SetRole { email: String, role: Role },
+    /// Delete every session of a user.
+    RevokeSessions { email: String },
+    /// Turn off two-factor authentication for a user who lost their authenticator and codes.
+    #[command(name = "disable-2fa")]
+    Disable2fa { email: String },
+    /// Delete all expired sessions and login attempts past their retention period.
+    PurgeSessions,
+    /// Show which embedded migrations are applied and which are pending.
+    MigrationStatus,
+    /// Apply all pending migrations.
+    Migrate,
+    /// Record migrations up to VERSION as applied without running them, for databases
+    /// created before migrations were tracked.
+    BaselineMigrations { version: i64 },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+
+    let config = Config::from_env();
+    let pool = db::create_pool(&config.database_url).await;
+
+    match cli.command {
+        Command::CreateUser { email, role, password_stdin } => {
+            let email = email.trim().to_lowercase();
+            if find_user_by_email(&pool, &email).await?.is_some() {
+                bail!("a user with email {} already exists", email);
+            }
+            let password = read_password(password_stdin)?;
+            let hash = hash_password(&password)?;
+            let id = create_user(&pool, &email, &hash, role).await?;
+            println!("created {} {} ({})", role, email, id);
+        }
+        Command::ResetPassword { email, password_stdin } => {
+            let user = require_user(&pool, &email).await?;
+            let password = read_password(password_stdin)?;
+            let hash = hash_password(&password)?;
+            set_password_hash(&pool, &user.id, &hash).await?;
+            let revoked = delete_user_sessions(&pool, &user.id).await?;
+            let tokens = delete_user_api_tokens(&pool, &user.id).await?;
+            println!(
+                "password updated for {}; revoked {} session(s) and {} API token(s)",
+                user.email, revoked, tokens
+            );
+        }
+        Command::ListUsers => {
+            for user in list_users(&pool).await? {
+                let id = user.id_as_uuid().map(|u| u.to_string()).unwrap_or_default();
+                let created_at = user
+                    .created_at
+                    .and_then(|t| t.format(&Rfc3339).ok())
+                    .unwrap_or_default();
+                println!("{}\t{}\t{}\t{}", id, user.email, user.role, created_at);
+            }
+        }
+        Command::SetRole { email, role } => {
+            let user = require_user(&pool, &email).await?;
+            set_role(&pool, &user.id, role).await?;
+            println!("{} is now {}", user.email, role);
+        }
+        Command::RevokeSessions { email } => {
+            let user = require_user(&pool, &email).await?;
+            let revoked = delete_user_sessions(&pool, &user.id).await?;
+            println!("revoked {} session(s) for {}", revoked, user.email);
+        }
+        Command::Disable2fa { email } => {
+            let user = require_user(&pool, &email).await?;
+            disable_two_factor(&pool, &user.id).await?;
+            println!("two-factor authentication disabled for {}", user.email);
+        }
+        Command::PurgeSessions => {
+            let purged = purge_expired_sessions(&pool).await?;
+            println!("purged {} expired session(s)", purged);
+            let attempts = purge_login_history(&pool).await?;
+            println!("purged {} old login attempt(s)", attempts);
+        }
+        Command::MigrationStatus => print_migration_status(&pool).await?,
+        Command::Migrate => {
+            db::run_migrations(&pool).await?;
+            print_migration_status(&pool).await?;
+        }
+        Command::BaselineMigrations { version } => {
+            let recorded = db::baseline_migrations(&pool, version).await?;
+            println!("recorded {} migration(s) as applied", recorded);
+            print_migration_status(&pool).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn print_migration_status(pool: &sqlx::MySqlPool) -> anyhow::Result<()> {
+    for migration in db::migration_status(pool).await? {
+        let installed_on = migration
+            .installed_on
+            .and_then(|t| t.format(&Rfc3339).ok());
+        match installed_on {
+            Some(installed_on) => println!("applied\t{:03}\t{}\t{}", migration.version, migration.description, installed_on),
+            None => println!("pending\t{:03}\t{}", migration.version, migration.description),
+        }
+    }
+    Ok(())
+}
+
+async fn require_user(pool: &sqlx::MySqlPool, email: &str) -> anyhow::Result<User> {
+    let email = email.trim().to_lowercase();
+    find_user_by_email(pool, &email)
+        .await?
+        .with_context(|| format!("no user with email {}", email))
+}
+
+/// Prompt twice on the terminal, or take one line from stdin for scripted use.
+fn read_password(from_stdin: bool) -> anyhow::Result<String> {
+    let password = if from_stdin {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        let password = rpassword::prompt_password("Password: ")?;
+        if rpassword::prompt_password("Confirm password: ")? != password {
+            bail!("passwords do not match");
+        }
+        password
+    };
+    if let Err(message) = validate_password(&password) {
+        bail!(message);
+    }
+    Ok(password)
+}
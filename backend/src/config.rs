@@ -1,26 +1,145 @@
 use std::env;
 
-pub const SESSION_DURATION_DAYS: i64 = 7;
-
 #[derive(Clone, Debug)]
 pub struct Config {
     pub database_url: String,
     pub session_secret: String,
     pub aws_region: String,
     pub aws_s3_bucket: String,
+    /// `s3`, `minio` or `local`; see `storage::from_config`.
+    pub storage_driver: String,
+    /// Endpoint of an S3-compatible service such as MinIO, e.g. `http://minio:9000`.
+    pub s3_endpoint: Option<String>,
+    /// Public base URL of stored files, when it differs from the driver's default.
+    pub storage_public_url: Option<String>,
+    /// Directory the `local` driver writes uploads to.
+    pub local_storage_dir: String,
+    /// Public base URL of the backend itself, without a trailing slash; the `local` driver
+    /// serves uploads under `/uploads` there.
+    pub public_base_url: String,
+    /// Largest accepted image upload, in bytes.
+    pub max_upload_bytes: usize,
+    /// Largest accepted image, in pixels (width × height).
+    pub max_image_pixels: u64,
     pub frontend_url: String,
     pub is_production: bool,
+    pub site_title: String,
+    /// Public base URL of the site, without a trailing slash; used for absolute links in feeds.
+    pub site_url: String,
+    /// Paths listed as `Disallow` in robots.txt.
+    pub robots_disallow: Vec<String>,
+    /// How often the background worker checks for scheduled content to publish.
+    pub publish_worker_interval_secs: u64,
+    /// Days a soft-deleted article or book stays in the trash before it is purged.
+    pub trash_retention_days: i64,
+    /// Apply pending migrations when the server starts.
+    pub run_migrations: bool,
+    /// Take the client IP from `X-Forwarded-For`; only enable behind a proxy that sets it.
+    pub trust_proxy_headers: bool,
+    /// Days of inactivity after which a session expires; each request pushes this forward.
+    pub session_idle_days: i64,
+    /// Days after login when a session expires no matter how active it is.
+    pub session_max_days: i64,
+    /// `smtp` or `log`; see `mail::from_config`.
+    pub mailer: String,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    /// Use STARTTLS; only turn off for a local mail catcher.
+    pub smtp_tls: bool,
+    /// Sender address, e.g. `My HP <no-reply@example.com>`.
+    pub mail_from: String,
+    /// Directory where the log mailer also writes `.eml` files.
+    pub mail_dir: Option<String>,
 }
 
 impl Config {
     pub fn from_env() -> Self {
+        let frontend_url = env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string());
+        let site_url = env::var("SITE_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| frontend_url.clone());
         Config {
             database_url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
             session_secret: env::var("SESSION_SECRET").expect("SESSION_SECRET must be set"),
             aws_region: env::var("AWS_REGION").unwrap_or_else(|_| "ap-northeast-1".to_string()),
             aws_s3_bucket: env::var("AWS_S3_BUCKET").unwrap_or_else(|_| "my-hp-images".to_string()),
-            frontend_url: env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string()),
+            storage_driver: env::var("STORAGE_DRIVER").unwrap_or_else(|_| "s3".to_string()),
+            s3_endpoint: env::var("S3_ENDPOINT")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(|v| v.trim_end_matches('/').to_string()),
+            storage_public_url: env::var("STORAGE_PUBLIC_URL")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(|v| v.trim_end_matches('/').to_string()),
+            local_storage_dir: env::var("LOCAL_STORAGE_DIR").unwrap_or_else(|_| "./uploads".to_string()),
+            public_base_url: env::var("PUBLIC_BASE_URL")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(|v| v.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| "http://localhost:3000".to_string()),
+            max_upload_bytes: env::var("MAX_UPLOAD_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10 * 1024 * 1024),
+            max_image_pixels: env::var("MAX_IMAGE_PIXELS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(40_000_000),
+            frontend_url,
             is_production: env::var("IS_PRODUCTION").unwrap_or_else(|_| "false".to_string()) == "true",
+            site_title: env::var("SITE_TITLE").unwrap_or_else(|_| "My HP".to_string()),
+            site_url: site_url.trim_end_matches('/').to_string(),
+            robots_disallow: env::var("ROBOTS_DISALLOW")
+                .unwrap_or_else(|_| "/admin".to_string())
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            publish_worker_interval_secs: env::var("PUBLISH_WORKER_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            trash_retention_days: env::var("TRASH_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            run_migrations: env::var("RUN_MIGRATIONS").unwrap_or_else(|_| "true".to_string()) == "true",
+            trust_proxy_headers: env::var("TRUST_PROXY_HEADERS").unwrap_or_else(|_| "false".to_string()) == "true",
+            session_idle_days: env::var("SESSION_IDLE_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(7),
+            session_max_days: env::var("SESSION_MAX_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            mailer: env::var("MAILER").unwrap_or_else(|_| "log".to_string()),
+            smtp_host: env::var("SMTP_HOST").ok().filter(|v| !v.is_empty()),
+            smtp_port: env::var("SMTP_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(587),
+            smtp_username: env::var("SMTP_USERNAME").ok().filter(|v| !v.is_empty()),
+            smtp_password: env::var("SMTP_PASSWORD").ok().filter(|v| !v.is_empty()),
+            smtp_tls: env::var("SMTP_TLS").unwrap_or_else(|_| "true".to_string()) == "true",
+            mail_from: env::var("MAIL_FROM").unwrap_or_else(|_| "no-reply@localhost".to_string()),
+            mail_dir: env::var("MAIL_DIR").ok().filter(|v| !v.is_empty()),
+        }
+    }
+
+    /// Public base URL of uploaded images, without a trailing slash.
+    pub fn image_base_url(&self) -> String {
+        if let Some(url) = &self.storage_public_url {
+            return url.clone();
+        }
+        match (self.storage_driver.as_str(), &self.s3_endpoint) {
+            ("local", _) => format!("{}/uploads", self.public_base_url),
+            (_, Some(endpoint)) => format!("{}/{}", endpoint, self.aws_s3_bucket),
+            _ => format!("https://{}.s3.{}.amazonaws.com", self.aws_s3_bucket, self.aws_region),
         }
     }
 }
@@ -0,0 +1,5 @@
+pub mod publishing;
+pub mod revisions;
+pub mod slug_history;
+pub mod tags;
+pub mod trash;
@@ -0,0 +1,68 @@
+use std::time::Duration;
+use sqlx::MySqlPool;
+use time::OffsetDateTime;
+
+/// The publication columns shared by articles and books.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PublishState {
+    pub published: bool,
+    pub publish_at: Option<OffsetDateTime>,
+    pub published_at: Option<OffsetDateTime>,
+}
+
+impl PublishState {
+    /// Apply a create/update request to the current state. `publish_at` follows partial-update
+    /// semantics: `None` keeps the current schedule, `Some(None)` clears it.
+    pub fn apply(self, published: Option<bool>, publish_at: Option<Option<OffsetDateTime>>) -> PublishState {
+        let now = OffsetDateTime::now_utc();
+        let new_published = published.unwrap_or(self.published);
+        if new_published {
+            return PublishState {
+                published: true,
+                publish_at: None,
+                published_at: self.published_at.or(Some(now)),
+            };
+        }
+
+        let mut new_publish_at = publish_at.unwrap_or(self.publish_at);
+        // Unpublishing must also cancel a schedule that is already due, or public queries keep showing the row
+        if published == Some(false) {
+            new_publish_at = new_publish_at.filter(|t| *t > now);
+        }
+        PublishState {
+            published: false,
+            publish_at: new_publish_at,
+            published_at: None,
+        }
+    }
+}
+
+/// Publish every article and book whose `publish_at` has passed, stamping `published_at`.
+/// Returns the number of rows promoted.
+pub async fn promote_due(pool: &MySqlPool) -> Result<u64, sqlx::Error> {
+    let mut promoted = 0;
+    for table in ["articles", "books"] {
+        promoted += sqlx::query(&format!(
+            "UPDATE {} SET published = true, published_at = NOW(), publish_at = NULL, updated_at = updated_at \
+             WHERE deleted_at IS NULL AND published = false AND publish_at IS NOT NULL AND publish_at <= NOW()",
+            table
+        ))
+        .execute(pool)
+        .await?
+        .rows_affected();
+    }
+    Ok(promoted)
+}
+
+/// Background loop that promotes scheduled content every `interval`.
+pub async fn run_publish_worker(pool: MySqlPool, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match promote_due(&pool).await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Published {} scheduled item(s)", n),
+            Err(e) => tracing::error!("Scheduled publish failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use crate::models::article::Article;
+use crate::models::revision::ArticleRevision;
+
+/// Load an article and lock its row until `conn`'s transaction ends, so concurrent saves
+/// take revision numbers one after the other.
+pub async fn lock_article(conn: &mut MySqlConnection, article_id: &[u8]) -> Result<Option<Article>, sqlx::Error> {
+    sqlx::query_as::<_, Article>(
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles \
+         WHERE id = ? AND deleted_at IS NULL FOR UPDATE"
+    )
+    .bind(article_id)
+    .fetch_optional(conn)
+    .await
+}
+
+/// Store the article's current title and markdown as its next revision. Call it with the
+/// row held by [`lock_article`].
+pub async fn record_revision(
+    conn: &mut MySqlConnection,
+    article: &Article,
+    created_by: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    let id = Uuid::new_v4().as_bytes().to_vec();
+    let created_by = created_by.map(|u| u.as_bytes().to_vec());
+    sqlx::query(
+        "INSERT INTO article_revisions (id, article_id, revision_number, title, markdown, created_by) \
+         SELECT ?, ?, COALESCE(MAX(revision_number), 0) + 1, ?, ?, ? FROM article_revisions WHERE article_id = ?"
+    )
+    .bind(&id)
+    .bind(&article.id)
+    .bind(&article.title)
+    .bind(&article.markdown)
+    .bind(&created_by)
+    .bind(&article.id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_revisions(pool: &MySqlPool, article_id: &[u8]) -> Result<Vec<ArticleRevision>, sqlx::Error> {
+    sqlx::query_as::<_, ArticleRevision>(
+        "SELECT id, article_id, revision_number, title, markdown, created_by, created_at FROM article_revisions \
+         WHERE article_id = ? ORDER BY revision_number DESC"
+    )
+    .bind(article_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_revision(
+    pool: &MySqlPool,
+    article_id: &[u8],
+    revision_id: &[u8],
+) -> Result<Option<ArticleRevision>, sqlx::Error> {
+    sqlx::query_as::<_, ArticleRevision>(
+        "SELECT id, article_id, revision_number, title, markdown, created_by, created_at FROM article_revisions \
+         WHERE article_id = ? AND id = ?"
+    )
+    .bind(article_id)
+    .bind(revision_id)
+    .fetch_optional(pool)
+    .await
+}
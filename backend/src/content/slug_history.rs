@@ -0,0 +1,58 @@
+use sqlx::{MySqlConnection, MySqlPool};
+
+#[derive(Debug, Clone, Copy)]
+pub enum SlugEntity {
+    Article,
+    Book,
+}
+
+impl SlugEntity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SlugEntity::Article => "article",
+            SlugEntity::Book => "book",
+        }
+    }
+}
+
+/// Remember that `old_slug` used to point at `entity_id`, and drop any history entry for
+/// `new_slug` since it is live again. Run it in the transaction that changes the slug.
+pub async fn record_slug_change(
+    conn: &mut MySqlConnection,
+    entity: SlugEntity,
+    entity_id: &[u8],
+    old_slug: &str,
+    new_slug: &str,
+) -> Result<(), sqlx::Error> {
+    if old_slug == new_slug {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT INTO slug_history (entity_type, slug, entity_id) VALUES (?, ?, ?) \
+         ON DUPLICATE KEY UPDATE entity_id = VALUES(entity_id), created_at = CURRENT_TIMESTAMP"
+    )
+    .bind(entity.as_str())
+    .bind(old_slug)
+    .bind(entity_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("DELETE FROM slug_history WHERE entity_type = ? AND slug = ?")
+        .bind(entity.as_str())
+        .bind(new_slug)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Resolve a retired slug to the id of the entity that used it.
+pub async fn find_slug_owner(
+    pool: &MySqlPool,
+    entity: SlugEntity,
+    slug: &str,
+) -> Result<Option<Vec<u8>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Vec<u8>>("SELECT entity_id FROM slug_history WHERE entity_type = ? AND slug = ?")
+        .bind(entity.as_str())
+        .bind(slug)
+        .fetch_optional(pool)
+        .await
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, MySql, MySqlConnection, MySqlPool, QueryBuilder};
+use uuid::Uuid;
+use crate::models::tag::TagResponse;
+use crate::utils::slug::slugify;
+
+pub const MAX_TAG_LENGTH: usize = 100;
+
+#[derive(FromRow)]
+struct ArticleTagRow {
+    article_id: Vec<u8>,
+    name: String,
+    slug: String,
+}
+
+/// The slug a tag name maps to. Names without any alphanumerics (emoji, punctuation) get
+/// one derived from a hash of the name, so saving the same tag twice finds the same row.
+pub fn tag_slug(name: &str) -> String {
+    let slug = slugify(name);
+    if !slug.is_empty() {
+        return slug;
+    }
+    let digest = Sha256::digest(name.to_lowercase().as_bytes());
+    format!("tag-{}", hex::encode(&digest[..6]))
+}
+
+/// Trim tag names, drop empty ones and deduplicate by slug, keeping the first spelling.
+pub fn normalize_tags(names: &[String]) -> Vec<(String, String)> {
+    let mut seen = Vec::new();
+    let mut tags = Vec::new();
+    for name in names {
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let slug = tag_slug(name);
+        if seen.contains(&slug) {
+            continue;
+        }
+        seen.push(slug.clone());
+        tags.push((name.to_string(), slug));
+    }
+    tags
+}
+
+/// Validate a tag list from a create/update request, returning an error message on failure.
+pub fn validate_tags(names: &[String]) -> Result<(), &'static str> {
+    if names.iter().any(|n| n.trim().chars().count() > MAX_TAG_LENGTH) {
+        return Err("Tags must be at most 100 characters");
+    }
+    Ok(())
+}
+
+/// Replace the tag set of an article, creating any tags that do not exist yet. Run it in
+/// the transaction that writes the article.
+pub async fn set_article_tags(
+    conn: &mut MySqlConnection,
+    article_id: &[u8],
+    names: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM article_tags WHERE article_id = ?")
+        .bind(article_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for (name, slug) in normalize_tags(names) {
+        let new_id = Uuid::new_v4().as_bytes().to_vec();
+        sqlx::query("INSERT INTO tags (id, name, slug) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE id = id")
+            .bind(&new_id)
+            .bind(&name)
+            .bind(&slug)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("INSERT IGNORE INTO article_tags (article_id, tag_id) SELECT ?, id FROM tags WHERE slug = ?")
+            .bind(article_id)
+            .bind(&slug)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Load the tags of several articles at once, keyed by article id.
+pub async fn tags_for_articles(
+    pool: &MySqlPool,
+    article_ids: &[Vec<u8>],
+) -> Result<HashMap<Vec<u8>, Vec<TagResponse>>, sqlx::Error> {
+    let mut map: HashMap<Vec<u8>, Vec<TagResponse>> = HashMap::new();
+    if article_ids.is_empty() {
+        return Ok(map);
+    }
+
+    let mut query = QueryBuilder::<MySql>::new(
+        "SELECT at.article_id, t.name, t.slug FROM article_tags at JOIN tags t ON t.id = at.tag_id WHERE at.article_id IN ("
+    );
+    let mut separated = query.separated(", ");
+    for id in article_ids {
+        separated.push_bind(id);
+    }
+    query.push(") ORDER BY t.name");
+
+    let rows = query.build_query_as::<ArticleTagRow>().fetch_all(pool).await?;
+    for row in rows {
+        map.entry(row.article_id).or_default().push(TagResponse {
+            name: row.name,
+            slug: row.slug,
+        });
+    }
+    Ok(map)
+}
+
+/// Load the tags of a single article.
+pub async fn tags_for_article(
+    pool: &MySqlPool,
+    article_id: &[u8],
+) -> Result<Vec<TagResponse>, sqlx::Error> {
+    let mut map = tags_for_articles(pool, &[article_id.to_vec()]).await?;
+    Ok(map.remove(article_id).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_only_names_get_a_stable_slug() {
+        assert_eq!(tag_slug("🦀"), tag_slug("🦀"));
+        assert_ne!(tag_slug("🦀"), tag_slug("🐍"));
+        assert!(tag_slug("🦀").starts_with("tag-"));
+        assert_eq!(tag_slug("Rust Lang"), "rust-lang");
+    }
+
+    #[test]
+    fn normalize_dedupes_by_slug() {
+        let names = vec![" Rust ".to_string(), "rust".to_string(), "🦀".to_string(), "🦀".to_string(), "".to_string()];
+        let tags = normalize_tags(&names);
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0], ("Rust".to_string(), "rust".to_string()));
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sqlx::types::Json;
+use sqlx::{FromRow, MySqlPool};
+use crate::models::image::ImageVariants;
+use crate::storage::Storage;
+
+#[derive(FromRow)]
+struct BookImages {
+    image_url: Option<String>,
+    image_variants: Option<Json<ImageVariants>>,
+}
+
+/// Soft-delete a row in `articles` or `books`. Returns false if it was missing or already trashed.
+pub async fn move_to_trash(pool: &MySqlPool, table: &str, id: &[u8]) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(&format!(
+        "UPDATE {} SET deleted_at = NOW(), updated_at = updated_at WHERE id = ? AND deleted_at IS NULL",
+        table
+    ))
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Permanently delete a trashed book and then its stored images. Returns false if the book
+/// is not in the trash.
+pub async fn purge_trashed_book(pool: &MySqlPool, storage: &dyn Storage, id: &[u8]) -> Result<bool, sqlx::Error> {
+    // The row lock keeps a concurrent restore from keeping a book whose images are being deleted
+    let mut tx = pool.begin().await?;
+    let images = sqlx::query_as::<_, BookImages>(
+        "SELECT image_url, image_variants FROM books WHERE id = ? AND deleted_at IS NOT NULL FOR UPDATE"
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(images) = images else {
+        return Ok(false);
+    };
+    sqlx::query("DELETE FROM books WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    delete_book_images(pool, storage, images).await?;
+    Ok(true)
+}
+
+/// Remove a purged book's image and its variants, unless another book still points at the
+/// same image. Storage failures are only logged: the row is gone and cannot be retried.
+async fn delete_book_images(pool: &MySqlPool, storage: &dyn Storage, images: BookImages) -> Result<(), sqlx::Error> {
+    let Some(image_url) = images.image_url else {
+        return Ok(());
+    };
+    let shared: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM books WHERE image_url = ?")
+        .bind(&image_url)
+        .fetch_one(pool)
+        .await?;
+    if shared > 0 {
+        return Ok(());
+    }
+
+    let mut urls = vec![image_url];
+    if let Some(Json(variants)) = &images.image_variants {
+        urls.extend(variants.urls().map(str::to_string));
+    }
+    urls.sort();
+    urls.dedup();
+    for url in urls {
+        let Some(key) = storage.key_for_url(&url) else {
+            continue;
+        };
+        if let Err(e) = storage.delete(&key).await {
+            tracing::warn!("Failed to delete {} from storage: {}", key, e);
+        }
+    }
+    Ok(())
+}
+
+/// Permanently delete trashed rows older than `retention_days`, along with their slug history
+/// and the images of purged books.
+pub async fn purge_expired(pool: &MySqlPool, storage: &dyn Storage, retention_days: i64) -> Result<u64, sqlx::Error> {
+    let mut purged = sqlx::query(
+        "DELETE FROM articles WHERE deleted_at IS NOT NULL AND deleted_at < NOW() - INTERVAL ? DAY"
+    )
+    .bind(retention_days)
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    let book_ids: Vec<Vec<u8>> = sqlx::query_scalar(
+        "SELECT id FROM books WHERE deleted_at IS NOT NULL AND deleted_at < NOW() - INTERVAL ? DAY"
+    )
+    .bind(retention_days)
+    .fetch_all(pool)
+    .await?;
+    for id in &book_ids {
+        if purge_trashed_book(pool, storage, id).await? {
+            purged += 1;
+        }
+    }
+
+    for (table, entity) in [("articles", "article"), ("books", "book")] {
+        sqlx::query(&format!(
+            "DELETE FROM slug_history WHERE entity_type = ? AND entity_id NOT IN (SELECT id FROM {})",
+            table
+        ))
+        .bind(entity)
+        .execute(pool)
+        .await?;
+    }
+    Ok(purged)
+}
+
+/// Background loop that empties the trash of items older than the retention period.
+pub async fn run_trash_worker(pool: MySqlPool, storage: Arc<dyn Storage>, retention_days: i64, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match purge_expired(&pool, storage.as_ref(), retention_days).await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Purged {} item(s) from trash", n),
+            Err(e) => tracing::error!("Trash purge failed: {}", e),
+        }
+    }
+}
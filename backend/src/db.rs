@@ -1,7 +1,94 @@
+use anyhow::bail;
+use sqlx::migrate::{Migrate, Migrator};
 use sqlx::mysql::MySqlPool;
+use time::OffsetDateTime;
+
+/// Migrations from `backend/migrations`, embedded at compile time. Applied versions are
+/// recorded in `_sqlx_migrations`.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
 pub async fn create_pool(database_url: &str) -> MySqlPool {
     MySqlPool::connect(database_url)
         .await
         .expect("Failed to create database pool")
 }
+
+/// One embedded migration and when it was applied, if it has been.
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: Option<OffsetDateTime>,
+}
+
+async fn table_exists(pool: &MySqlPool, table: &str) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = DATABASE() AND table_name = ?"
+    )
+    .bind(table)
+    .fetch_one(pool)
+    .await?;
+    Ok(count > 0)
+}
+
+/// Apply every pending migration.
+///
+/// A schema created by the old `docker-entrypoint-initdb.d` mount has tables but no history;
+/// running the migrations over it would fail half-way, so it has to be baselined first.
+pub async fn run_migrations(pool: &MySqlPool) -> anyhow::Result<()> {
+    if !table_exists(pool, "_sqlx_migrations").await? && table_exists(pool, "users").await? {
+        bail!(
+            "database has tables but no migration history; record the migrations it already has with \
+             `backend-admin baseline-migrations <version>` and start again"
+        );
+    }
+    MIGRATOR.run(pool).await?;
+    Ok(())
+}
+
+pub async fn migration_status(pool: &MySqlPool) -> Result<Vec<MigrationStatus>, sqlx::Error> {
+    let applied: Vec<(i64, OffsetDateTime)> = if table_exists(pool, "_sqlx_migrations").await? {
+        sqlx::query_as("SELECT version, installed_on FROM _sqlx_migrations WHERE success = TRUE")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(MIGRATOR
+        .iter()
+        .map(|m| MigrationStatus {
+            version: m.version,
+            description: m.description.to_string(),
+            installed_on: applied
+                .iter()
+                .find(|(version, _)| *version == m.version)
+                .map(|(_, installed_on)| *installed_on),
+        })
+        .collect())
+}
+
+/// Mark every migration up to and including `version` as applied without running it.
+/// Returns the number of migrations recorded.
+pub async fn baseline_migrations(pool: &MySqlPool, version: i64) -> anyhow::Result<usize> {
+    if !MIGRATOR.version_exists(version) {
+        bail!("no migration with version {}", version);
+    }
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+
+    let mut recorded = 0;
+    for migration in MIGRATOR.iter().filter(|m| m.version <= version) {
+        // Same row shape sqlx writes itself; -1 marks it as never actually executed
+        let result = sqlx::query(
+            "INSERT IGNORE INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+             VALUES (?, ?, TRUE, ?, -1)"
+        )
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .execute(&mut *conn)
+        .await?;
+        recorded += result.rows_affected() as usize;
+    }
+    Ok(recorded)
+}
@@ -0,0 +1,174 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+use crate::middleware::request_id::current_request_id;
+use crate::storage::StorageError;
+use crate::utils::image::ImageError;
+use crate::validation::FieldError;
+
+/// Error type shared by every handler. Renders as
+/// `{ "code", "message", "details", "request_id" }` with a matching status code.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    /// One or more request fields failed validation; listed in `details`.
+    #[error("Validation failed")]
+    Validation(Vec<FieldError>),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    /// A cookie-authenticated mutation without a valid `X-CSRF-Token`.
+    #[error("Missing or invalid CSRF token")]
+    CsrfFailed,
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error("{0}")]
+    UnsupportedMediaType(String),
+    /// Rate limited; the client may retry after this many seconds.
+    #[error("Too many attempts; try again in {0} seconds")]
+    TooManyRequests(u64),
+    #[error("database error: {0}")]
+    Database(#[source] sqlx::Error),
+    #[error("storage error: {0}")]
+    Storage(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        ApiError::BadRequest(message.into())
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        ApiError::Unauthorized(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        ApiError::Forbidden(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        ApiError::NotFound(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        ApiError::Conflict(message.into())
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        ApiError::Internal(message.into())
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) | ApiError::CsrfFailed => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Database(_) | ApiError::Storage(_) | ApiError::Internal(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    /// Stable, machine-readable error code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Validation(_) => "validation_failed",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::CsrfFailed => "csrf_failed",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::UnsupportedMediaType(_) => "unsupported_media_type",
+            ApiError::TooManyRequests(_) => "too_many_requests",
+            ApiError::Database(_) => "database_error",
+            ApiError::Storage(_) => "storage_error",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            ApiError::Validation(errors) => json!(errors),
+            ApiError::TooManyRequests(retry_after) => json!({ "retry_after": retry_after }),
+            _ => Value::Null,
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => ApiError::not_found("Not found"),
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                ApiError::conflict("A resource with the same unique value already exists")
+            }
+            _ => ApiError::Database(e),
+        }
+    }
+}
+
+impl From<ImageError> for ApiError {
+    fn from(e: ImageError) -> Self {
+        match e {
+            ImageError::TooLarge { .. } | ImageError::TooManyPixels { .. } => ApiError::PayloadTooLarge(e.to_string()),
+            ImageError::UnsupportedFormat | ImageError::AvifUnavailable => ApiError::UnsupportedMediaType(e.to_string()),
+            ImageError::Decode(_) => ApiError::bad_request(e.to_string()),
+            ImageError::Encode(_) => ApiError::internal(e.to_string()),
+        }
+    }
+}
+
+impl From<StorageError> for ApiError {
+    fn from(e: StorageError) -> Self {
+        match e {
+            StorageError::InvalidKey(_) => ApiError::not_found("Not found"),
+            _ => ApiError::Storage(e.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let request_id = current_request_id();
+
+        // Server-side failures are logged in full; clients only get a generic message
+        let message = if status.is_server_error() {
+            tracing::error!(request_id = request_id.as_deref().unwrap_or("-"), "{}", self);
+            "Internal server error".to_string()
+        } else {
+            self.to_string()
+        };
+
+        let body = json!({
+            "code": self.code(),
+            "message": message,
+            "details": self.details(),
+            "request_id": request_id,
+        });
+        let mut response = (status, Json(body)).into_response();
+        if let ApiError::TooManyRequests(retry_after) = self {
+            response.headers_mut().insert(header::RETRY_AFTER, retry_after.into());
+        }
+        response
+    }
+}
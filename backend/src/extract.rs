@@ -0,0 +1,115 @@
+//! Drop-in replacements for axum's `Json`, `Query` and `Path` whose rejections render as
+//! [`ApiError`], so malformed bodies and parameters get the same JSON error shape as
+//! everything else.
+
+use axum::{
+    async_trait,
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        FromRequest, FromRequestParts, Request,
+    },
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use crate::error::ApiError;
+
+/// JSON body extractor and response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+/// Query string extractor.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+/// Path parameter extractor.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Path<T>(pub T);
+
+fn rejection(status: StatusCode, message: String) -> ApiError {
+    match status {
+        StatusCode::PAYLOAD_TOO_LARGE => ApiError::PayloadTooLarge(message),
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => ApiError::UnsupportedMediaType(message),
+        status if status.is_server_error() => ApiError::internal(message),
+        _ => ApiError::bad_request(message),
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(e: JsonRejection) -> Self {
+        rejection(e.status(), e.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(e: QueryRejection) -> Self {
+        rejection(e.status(), e.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(e: PathRejection) -> Self {
+        rejection(e.status(), e.body_text())
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::Json(value) = axum::Json::<T>::from_request(req, state).await?;
+        Ok(Json(value))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(value) = axum::extract::Query::<T>::from_request_parts(parts, state).await?;
+        Ok(Query(value))
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Path(value) = axum::extract::Path::<T>::from_request_parts(parts, state).await?;
+        Ok(Path(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejection_statuses_map_to_api_errors() {
+        assert!(matches!(rejection(StatusCode::PAYLOAD_TOO_LARGE, String::new()), ApiError::PayloadTooLarge(_)));
+        assert!(matches!(rejection(StatusCode::UNSUPPORTED_MEDIA_TYPE, String::new()), ApiError::UnsupportedMediaType(_)));
+        assert!(matches!(rejection(StatusCode::UNPROCESSABLE_ENTITY, String::new()), ApiError::BadRequest(_)));
+        assert!(matches!(rejection(StatusCode::INTERNAL_SERVER_ERROR, String::new()), ApiError::Internal(_)));
+    }
+}
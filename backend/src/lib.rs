@@ -0,0 +1,30 @@
+use std::sync::Arc;
+use sqlx::MySqlPool;
+
+pub mod auth;
+pub mod config;
+pub mod content;
+pub mod db;
+pub mod error;
+pub mod extract;
+pub mod mail;
+pub mod middleware;
+pub mod models;
+pub mod routes;
+pub mod storage;
+pub mod utils;
+pub mod validation;
+
+use auth::throttle::LoginThrottle;
+use config::Config;
+use mail::Mailer;
+use storage::Storage;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: MySqlPool,
+    pub config: Config,
+    pub storage: Arc<dyn Storage>,
+    pub login_throttle: Arc<LoginThrottle>,
+    pub mailer: Arc<dyn Mailer>,
+}
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+use axum::async_trait;
+use time::OffsetDateTime;
+use uuid::Uuid;
+use super::{Email, MailError, Mailer};
+
+/// Development mailer: logs the recipient and subject of every message and, with `MAIL_DIR`
+/// set, writes the full message there as an `.eml` file that mail clients can open. Bodies
+/// carry reset links, so they never go to the log.
+pub struct LogMailer {
+    dir: Option<PathBuf>,
+}
+
+impl LogMailer {
+    pub fn new(dir: Option<String>) -> Self {
+        LogMailer { dir: dir.map(PathBuf::from) }
+    }
+}
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        tracing::info!(to = %email.to, subject = %email.subject, "mail not sent (log mailer)");
+
+        if let Some(dir) = &self.dir {
+            tokio::fs::create_dir_all(dir).await?;
+            let name = format!("{}-{}.eml", OffsetDateTime::now_utc().unix_timestamp(), Uuid::new_v4().simple());
+            let message = format!(
+                "To: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+                email.to, email.subject, email.body
+            );
+            tokio::fs::write(dir.join(name), message).await?;
+        }
+        Ok(())
+    }
+}
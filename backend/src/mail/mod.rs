@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use axum::async_trait;
+use crate::config::Config;
+
+mod log;
+mod smtp;
+
+pub use self::log::LogMailer;
+pub use self::smtp::SmtpMailer;
+
+/// A plain-text message to one recipient.
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailError {
+    #[error("invalid address: {0}")]
+    Address(String),
+    #[error("could not build message: {0}")]
+    Message(String),
+    #[error("smtp: {0}")]
+    Smtp(String),
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Outgoing mail. Chosen once at startup with `MAILER`; handlers only see the trait.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Email) -> Result<(), MailError>;
+}
+
+/// The mailer selected by `MAILER`: `smtp`, or `log` (the default) for local development.
+/// Production refuses `log`, since password resets would never reach anyone.
+pub fn from_config(config: &Config) -> Arc<dyn Mailer> {
+    match config.mailer.as_str() {
+        "smtp" => Arc::new(SmtpMailer::from_config(config).expect("invalid SMTP configuration")),
+        "log" if config.is_production => panic!("MAILER=log is not allowed with IS_PRODUCTION=true; configure SMTP"),
+        "log" => Arc::new(LogMailer::new(config.mail_dir.clone())),
+        other => panic!("unknown MAILER: {} (expected smtp or log)", other),
+    }
+}
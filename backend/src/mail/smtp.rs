@@ -0,0 +1,58 @@
+use axum::async_trait;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use crate::config::Config;
+use super::{Email, MailError, Mailer};
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn from_config(config: &Config) -> Result<Self, MailError> {
+        let host = config
+            .smtp_host
+            .as_deref()
+            .ok_or_else(|| MailError::Smtp("SMTP_HOST must be set".to_string()))?;
+        // STARTTLS unless turned off, e.g. for a local catcher like MailHog
+        let mut builder = if config.smtp_tls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(|e| MailError::Smtp(e.to_string()))?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+        };
+        builder = builder.port(config.smtp_port);
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        let from = config
+            .mail_from
+            .parse()
+            .map_err(|_| MailError::Address(config.mail_from.clone()))?;
+        Ok(SmtpMailer {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        let to: Mailbox = email.to.parse().map_err(|_| MailError::Address(email.to.clone()))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&email.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body.clone())
+            .map_err(|e| MailError::Message(e.to_string()))?;
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| MailError::Smtp(e.to_string()))?;
+        Ok(())
+    }
+}
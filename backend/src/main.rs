@@ -1,31 +1,21 @@
 use axum::{
     routing::{delete, get, post, put},
+    extract::DefaultBodyLimit,
     Router,
 };
 use axum::middleware as axum_middleware;
-use sqlx::MySqlPool;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use tower_cookies::CookieManagerLayer;
 use tower_http::cors::CorsLayer;
-use axum::http::{HeaderValue, Method, header};
+use axum::http::{HeaderName, HeaderValue, Method, header};
 use tracing_subscriber::EnvFilter;
 
-mod config;
-mod db;
-mod models;
-mod routes;
-mod middleware;
-mod auth;
-mod utils;
-
-use config::Config;
-
-#[derive(Clone)]
-pub struct AppState {
-    pub pool: MySqlPool,
-    pub config: Config,
-    pub s3_client: aws_sdk_s3::Client,
-}
+use backend::auth::csrf::CSRF_HEADER;
+use backend::auth::throttle::{run_login_history_worker, LoginThrottle};
+use backend::config::Config;
+use backend::{content, db, mail, middleware, routes, storage, AppState};
 
 #[tokio::main]
 async fn main() {
@@ -38,20 +28,59 @@ async fn main() {
     let config = Config::from_env();
     let pool = db::create_pool(&config.database_url).await;
 
-    let aws_config = aws_config::load_from_env().await;
-    let s3_client = aws_sdk_s3::Client::new(&aws_config);
+    // `--migrate-only` applies migrations regardless of RUN_MIGRATIONS, reports and exits
+    let migrate_only = std::env::args().any(|arg| arg == "--migrate-only");
+    if migrate_only {
+        // Read the history first so the report can tell what this run applied
+        let status = db::migration_status(&pool).await.expect("Failed to read migration status");
+        db::run_migrations(&pool).await.expect("Failed to run migrations");
+        for migration in status {
+            let state = if migration.installed_on.is_some() { "applied" } else { "new" };
+            println!("{}\t{:03}\t{}", state, migration.version, migration.description);
+        }
+        return;
+    }
+    if config.run_migrations {
+        db::run_migrations(&pool).await.expect("Failed to run migrations");
+    }
+
+    let storage = storage::from_config(&config).await;
+
+    tokio::spawn(content::publishing::run_publish_worker(
+        pool.clone(),
+        Duration::from_secs(config.publish_worker_interval_secs.max(1)),
+    ));
+
+    tokio::spawn(content::trash::run_trash_worker(
+        pool.clone(),
+        storage.clone(),
+        config.trash_retention_days,
+        Duration::from_secs(60 * 60),
+    ));
+
+    tokio::spawn(run_login_history_worker(
+        pool.clone(),
+        Duration::from_secs(60 * 60),
+    ));
 
     let state = AppState {
         pool,
         config: config.clone(),
-        s3_client,
+        storage,
+        login_throttle: Arc::new(LoginThrottle::default()),
+        mailer: mail::from_config(&config),
     };
 
     let frontend_url = config.frontend_url.clone();
     let cors = CorsLayer::new()
         .allow_origin(frontend_url.parse::<HeaderValue>().expect("Invalid FRONTEND_URL"))
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            HeaderName::from_static(CSRF_HEADER),
+        ])
+        .expose_headers([middleware::request_id::REQUEST_ID_HEADER.clone(), header::RETRY_AFTER])
         .allow_credentials(true);
 
     let admin_router = Router::new()
@@ -60,28 +89,90 @@ async fn main() {
         .route("/articles/:id", get(routes::admin::articles::get_article_by_id))
         .route("/articles/:id", put(routes::admin::articles::update_article))
         .route("/articles/:id", delete(routes::admin::articles::delete_article))
+        .route("/articles/:id/regenerate-slug", post(routes::admin::articles::regenerate_slug))
+        .route("/articles/:id/preview-tokens", get(routes::admin::preview::list_previews))
+        .route("/articles/:id/preview-tokens", post(routes::admin::preview::create_preview))
+        .route("/articles/:id/preview-tokens/:token_id", delete(routes::admin::preview::revoke_preview))
+        .route("/articles/:id/revisions", get(routes::admin::revisions::list_article_revisions))
+        .route("/articles/:id/revisions/diff", get(routes::admin::revisions::diff_article_revisions))
+        .route("/articles/:id/revisions/:revision_id", get(routes::admin::revisions::get_article_revision))
+        .route("/articles/:id/revisions/:revision_id/restore", post(routes::admin::revisions::restore_article_revision))
+        .route("/books", get(routes::admin::books::list_books))
         .route("/books", post(routes::admin::books::create_book))
+        .route("/books/:id", get(routes::admin::books::get_book_by_id))
         .route("/books/:id", put(routes::admin::books::update_book))
         .route("/books/:id", delete(routes::admin::books::delete_book))
-        .route("/upload-image", post(routes::admin::upload::upload_image))
-        .layer(axum_middleware::from_fn_with_state(state.clone(), middleware::auth::require_auth));
+        .route("/search", get(routes::admin::search::search))
+        .route("/trash", get(routes::admin::trash::list_trash))
+        .route("/trash/:id", delete(routes::admin::trash::purge_from_trash))
+        .route("/trash/:id/restore", post(routes::admin::trash::restore_from_trash))
+        .route("/tags", get(routes::admin::tags::list_tags))
+        .route("/tags/merge", post(routes::admin::tags::merge_tags))
+        .route("/tags/:slug", put(routes::admin::tags::rename_tag))
+        .route(
+            "/upload-image",
+            post(routes::admin::upload::upload_image).layer(DefaultBodyLimit::max(
+                config.max_upload_bytes + routes::admin::upload::MULTIPART_OVERHEAD_BYTES,
+            )),
+        )
+        .route("/login-attempts", get(routes::admin::security::list_login_attempts))
+        .route("/login-lockouts/:scope/:key", delete(routes::admin::security::delete_lockout))
+        .route("/account/password", post(routes::admin::account::change_password))
+        .route("/sessions", get(routes::admin::sessions::list_sessions))
+        .route("/sessions", delete(routes::admin::sessions::delete_all_sessions))
+        .route("/sessions/:id", delete(routes::admin::sessions::delete_session))
+        .route("/tokens", get(routes::admin::tokens::list_tokens))
+        .route("/tokens", post(routes::admin::tokens::create_token))
+        .route("/tokens/:id", delete(routes::admin::tokens::revoke_token))
+        .route("/2fa", get(routes::admin::two_factor::get_status))
+        .route("/2fa/setup", post(routes::admin::two_factor::setup))
+        .route("/2fa/enable", post(routes::admin::two_factor::enable))
+        .route("/2fa/disable", post(routes::admin::two_factor::disable))
+        .route("/2fa/recovery-codes", post(routes::admin::two_factor::regenerate_recovery_codes))
+        .layer(axum_middleware::from_fn_with_state(state.clone(), middleware::auth::require_auth))
+        .layer(axum_middleware::from_fn_with_state(state.clone(), middleware::csrf::require_csrf));
+
+    let session_router = Router::new()
+        .route("/login", post(routes::auth::login))
+        .route("/login/2fa", post(routes::auth::login_second_factor))
+        .route("/logout", post(routes::auth::logout))
+        .route("/password/forgot", post(routes::password_reset::forgot_password))
+        .route("/password/reset", post(routes::password_reset::reset_password))
+        .route_layer(axum_middleware::from_fn_with_state(state.clone(), middleware::csrf::require_csrf));
 
     let app = Router::new()
         .route("/health", get(routes::health::health))
         .route("/articles", get(routes::articles::list_articles))
         .route("/articles/:slug", get(routes::articles::get_article))
+        .route("/preview/:token", get(routes::preview::get_preview))
         .route("/books", get(routes::books::list_books))
         .route("/books/:slug", get(routes::books::get_book))
-        .route("/login", post(routes::auth::login))
-        .route("/logout", post(routes::auth::logout))
+        .route("/search", get(routes::search::search))
+        .route("/sitemap.xml", get(routes::sitemap::sitemap))
+        .route("/sitemaps/:file", get(routes::sitemap::sitemap_page))
+        .route("/robots.txt", get(routes::sitemap::robots))
+        .route("/uploads/*key", get(routes::uploads::serve_upload))
+        .route("/feed.xml", get(routes::feeds::atom_feed))
+        .route("/rss.xml", get(routes::feeds::rss_feed))
+        .route("/feed.json", get(routes::feeds::json_feed_handler))
+        .route("/tags", get(routes::tags::list_tags))
+        .route("/tags/:slug/articles", get(routes::tags::list_tag_articles))
+        .route("/tags/:slug/feed.xml", get(routes::feeds::tag_atom_feed))
+        .route("/tags/:slug/rss.xml", get(routes::feeds::tag_rss_feed))
+        .route("/tags/:slug/feed.json", get(routes::feeds::tag_json_feed))
         .route("/me", get(routes::auth::me))
+        .route("/csrf-token", get(routes::auth::csrf_token))
+        .merge(session_router)
         .nest("/admin", admin_router)
         .layer(CookieManagerLayer::new())
         .layer(cors)
+        .layer(axum_middleware::from_fn(middleware::request_id::assign_request_id))
         .with_state(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
     tracing::info!("Backend listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 }
@@ -1,33 +1,80 @@
 use axum::{
     extract::{Request, State},
+    http::header::AUTHORIZATION,
     middleware::Next,
     response::{IntoResponse, Response},
-    http::StatusCode,
-    Json,
 };
-use serde_json::json;
+use time::OffsetDateTime;
 use tower_cookies::Cookies;
-use crate::auth::session::get_user_by_session;
+use crate::auth::api_tokens::{find_api_token, required_scope, touch_api_token};
+use crate::auth::session::{auth_cookie, find_session, touch_session, SESSION_COOKIE};
+use crate::error::ApiError;
 use crate::AppState;
 
+/// Accepts either a personal access token (`Authorization: Bearer`) or the session cookie.
 pub async fn require_auth(
     State(state): State<AppState>,
     cookies: Cookies,
-    mut req: Request,
+    req: Request,
     next: Next,
 ) -> Response {
-    let session_id = match cookies.get("session_id").map(|c| c.value().to_string()) {
-        Some(id) => id,
-        None => {
-            return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" }))).into_response();
+    let bearer = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string());
+    match bearer {
+        Some(token) => authenticate_token(state, token, req, next).await,
+        None => authenticate_session(state, cookies, req, next).await,
+    }
+}
+
+async fn authenticate_token(state: AppState, token: String, mut req: Request, next: Next) -> Response {
+    let Some((api_token, user)) = find_api_token(&state.pool, &token).await else {
+        return ApiError::unauthorized("invalid or expired token").into_response();
+    };
+
+    let path = req.uri().path();
+    let path = path.strip_prefix("/admin").unwrap_or(path);
+    match required_scope(req.method(), path) {
+        Some(scope) if api_token.has_scope(scope) => {}
+        Some(scope) => {
+            return ApiError::forbidden(format!("This token lacks the {} scope", scope)).into_response();
         }
+        None => return ApiError::forbidden("This endpoint requires a session").into_response(),
+    }
+
+    if let Err(e) = touch_api_token(&state.pool, &api_token).await {
+        tracing::warn!("failed to touch api token: {}", e);
+    }
+
+    req.extensions_mut().insert(user);
+    req.extensions_mut().insert(api_token);
+    next.run(req).await
+}
+
+async fn authenticate_session(state: AppState, cookies: Cookies, mut req: Request, next: Next) -> Response {
+    let session_id = match cookies.get(SESSION_COOKIE).map(|c| c.value().to_string()) {
+        Some(id) => id,
+        None => return ApiError::unauthorized("unauthorized").into_response(),
+    };
+
+    let Some((session, user)) = find_session(&state.pool, &session_id).await else {
+        return ApiError::unauthorized("unauthorized").into_response();
     };
 
-    match get_user_by_session(&state.pool, &session_id).await {
-        Some(user) => {
-            req.extensions_mut().insert(user);
-            next.run(req).await
+    // Sliding expiry: renew the cookie whenever the stored expiry moves
+    match touch_session(&state.pool, &state.config, &session).await {
+        Ok(Some(expires_at)) => {
+            let max_age = expires_at - OffsetDateTime::now_utc();
+            cookies.add(auth_cookie(&state.config, SESSION_COOKIE, session_id, max_age));
         }
-        None => (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" }))).into_response(),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("failed to touch session: {}", e),
     }
+
+    req.extensions_mut().insert(user);
+    req.extensions_mut().insert(session);
+    next.run(req).await
 }
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tower_cookies::Cookies;
+use crate::auth::csrf::{verify_token, CSRF_HEADER};
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Reject state-changing requests that do not echo the CSRF token in `X-CSRF-Token`.
+/// Bearer-authenticated requests are exempt: browsers never attach that header on their own.
+pub async fn require_csrf(
+    State(state): State<AppState>,
+    cookies: Cookies,
+    req: Request,
+    next: Next,
+) -> Response {
+    let safe = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let bearer = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("Bearer "));
+    if safe || bearer {
+        return next.run(req).await;
+    }
+
+    let token = req.headers().get(CSRF_HEADER).and_then(|v| v.to_str().ok());
+    match token {
+        Some(token) if verify_token(&state.config, &cookies, token) => next.run(req).await,
+        _ => ApiError::CsrfFailed.into_response(),
+    }
+}
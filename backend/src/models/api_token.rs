@@ -0,0 +1,130 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+/// What a personal access token may do. A token never grants more than its owner's role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenScope {
+    /// List and read articles, tags and admin search.
+    #[serde(rename = "articles:read")]
+    ArticlesRead,
+    /// Create, update and delete articles, their previews and revisions.
+    #[serde(rename = "articles:write")]
+    ArticlesWrite,
+    #[serde(rename = "books:read")]
+    BooksRead,
+    #[serde(rename = "books:write")]
+    BooksWrite,
+    /// Rename and merge tags.
+    #[serde(rename = "tags:write")]
+    TagsWrite,
+    /// Upload images.
+    #[serde(rename = "upload")]
+    Upload,
+}
+
+impl TokenScope {
+    pub const ALL: [TokenScope; 6] = [
+        TokenScope::ArticlesRead,
+        TokenScope::ArticlesWrite,
+        TokenScope::BooksRead,
+        TokenScope::BooksWrite,
+        TokenScope::TagsWrite,
+        TokenScope::Upload,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenScope::ArticlesRead => "articles:read",
+            TokenScope::ArticlesWrite => "articles:write",
+            TokenScope::BooksRead => "books:read",
+            TokenScope::BooksWrite => "books:write",
+            TokenScope::TagsWrite => "tags:write",
+            TokenScope::Upload => "upload",
+        }
+    }
+}
+
+impl fmt::Display for TokenScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TokenScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TokenScope::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| format!("unknown scope: {}", s))
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiToken {
+    pub id: Vec<u8>,
+    pub user_id: Vec<u8>,
+    pub name: String,
+    pub token_prefix: String,
+    /// Comma-separated `TokenScope` names.
+    pub scopes: String,
+    pub last_used_at: Option<OffsetDateTime>,
+    pub expires_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+impl ApiToken {
+    /// Scopes stored for the token; names this build does not know are ignored.
+    pub fn scopes(&self) -> Vec<TokenScope> {
+        self.scopes.split(',').filter_map(|s| s.trim().parse().ok()).collect()
+    }
+
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes().contains(&scope)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// Lifetime in days; the token never expires when omitted.
+    pub expires_in_days: Option<i64>,
+}
+
+/// Token metadata for the admin list; the token itself is only returned on creation.
+#[derive(Debug, Serialize)]
+pub struct ApiTokenResponse {
+    pub id: String,
+    pub name: String,
+    /// First characters of the token, to tell tokens apart.
+    pub prefix: String,
+    pub scopes: Vec<TokenScope>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(t: ApiToken) -> Self {
+        let id = uuid::Uuid::from_slice(&t.id)
+            .map(|u| u.to_string())
+            .unwrap_or_default();
+        ApiTokenResponse {
+            id,
+            scopes: t.scopes(),
+            name: t.name,
+            prefix: t.token_prefix,
+            last_used_at: t.last_used_at,
+            expires_at: t.expires_at,
+            created_at: t.created_at,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use time::OffsetDateTime;
+use crate::models::datetime::{deserialize_nullable_rfc3339, publish_status};
+use crate::models::tag::TagResponse;
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct Article {
@@ -10,6 +12,8 @@ pub struct Article {
     pub markdown: String,
     pub html: String,
     pub published: bool,
+    pub publish_at: Option<OffsetDateTime>,
+    pub published_at: Option<OffsetDateTime>,
     pub created_at: Option<OffsetDateTime>,
     pub updated_at: Option<OffsetDateTime>,
 }
@@ -19,6 +23,11 @@ pub struct CreateArticleRequest {
     pub title: String,
     pub markdown: String,
     pub published: Option<bool>,
+    /// Publish automatically once this time has passed.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub publish_at: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,9 +35,25 @@ pub struct UpdateArticleRequest {
     pub title: Option<String>,
     pub markdown: Option<String>,
     pub published: Option<bool>,
+    /// `null` clears the schedule; omitted leaves it unchanged.
+    #[serde(default, deserialize_with = "deserialize_nullable_rfc3339")]
+    pub publish_at: Option<Option<OffsetDateTime>>,
+    /// Replaces the article's tag set when present.
+    pub tags: Option<Vec<String>>,
 }
 
-/// Full article response (admin), includes markdown and published flag.
+/// Query parameters for the public article listing.
+#[derive(Debug, Deserialize)]
+pub struct ListArticlesQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub from: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub to: Option<OffsetDateTime>,
+}
+
+/// Full article response (admin), includes markdown, published flag and schedule.
 #[derive(Debug, Serialize)]
 pub struct AdminArticleResponse {
     pub id: String,
@@ -37,6 +62,13 @@ pub struct AdminArticleResponse {
     pub markdown: String,
     pub html: String,
     pub published: bool,
+    /// One of `published`, `scheduled` or `draft`.
+    pub status: &'static str,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub publish_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub published_at: Option<OffsetDateTime>,
+    pub tags: Vec<TagResponse>,
     #[serde(with = "time::serde::rfc3339::option")]
     pub created_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339::option")]
@@ -55,18 +87,23 @@ impl From<Article> for AdminArticleResponse {
             markdown: a.markdown,
             html: a.html,
             published: a.published,
+            status: publish_status(a.published, a.publish_at),
+            publish_at: a.publish_at,
+            published_at: a.published_at,
+            tags: Vec::new(),
             created_at: a.created_at,
             updated_at: a.updated_at,
         }
     }
 }
 
-/// Public list response: id, title, slug, created_at, updated_at only.
+/// Public list response: id, title, slug, tags, created_at, updated_at only.
 #[derive(Debug, Serialize)]
 pub struct PublicArticleListResponse {
     pub id: String,
     pub title: String,
     pub slug: String,
+    pub tags: Vec<TagResponse>,
     #[serde(with = "time::serde::rfc3339::option")]
     pub created_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339::option")]
@@ -82,19 +119,21 @@ impl From<Article> for PublicArticleListResponse {
             id,
             title: a.title,
             slug: a.slug,
+            tags: Vec::new(),
             created_at: a.created_at,
             updated_at: a.updated_at,
         }
     }
 }
 
-/// Public detail response: id, title, slug, html, created_at, updated_at (no markdown).
+/// Public detail response: id, title, slug, html, tags, created_at, updated_at (no markdown).
 #[derive(Debug, Serialize)]
 pub struct PublicArticleDetailResponse {
     pub id: String,
     pub title: String,
     pub slug: String,
     pub html: String,
+    pub tags: Vec<TagResponse>,
     #[serde(with = "time::serde::rfc3339::option")]
     pub created_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339::option")]
@@ -111,6 +150,7 @@ impl From<Article> for PublicArticleDetailResponse {
             title: a.title,
             slug: a.slug,
             html: a.html,
+            tags: Vec::new(),
             created_at: a.created_at,
             updated_at: a.updated_at,
         }
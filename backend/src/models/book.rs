@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 use time::OffsetDateTime;
+use crate::models::datetime::{deserialize_nullable_rfc3339, publish_status};
+use crate::models::image::ImageVariants;
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct Book {
@@ -10,7 +13,10 @@ pub struct Book {
     pub markdown: String,
     pub html: String,
     pub image_url: Option<String>,
+    pub image_variants: Option<Json<ImageVariants>>,
     pub published: bool,
+    pub publish_at: Option<OffsetDateTime>,
+    pub published_at: Option<OffsetDateTime>,
     pub created_at: Option<OffsetDateTime>,
     pub updated_at: Option<OffsetDateTime>,
 }
@@ -18,10 +24,17 @@ pub struct Book {
 #[derive(Debug, Deserialize)]
 pub struct CreateBookRequest {
     pub title: String,
-    pub slug: String,
+    /// Generated from the title when omitted or empty.
+    #[serde(default)]
+    pub slug: Option<String>,
     pub markdown: String,
     pub image_url: Option<String>,
+    /// The set `/admin/upload-image` returned for `image_url`.
+    pub image_variants: Option<ImageVariants>,
     pub published: Option<bool>,
+    /// Publish automatically once this time has passed.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub publish_at: Option<OffsetDateTime>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -30,7 +43,24 @@ pub struct UpdateBookRequest {
     pub slug: Option<String>,
     pub markdown: Option<String>,
     pub image_url: Option<String>,
+    /// Replaces the stored set; dropped when `image_url` changes without one.
+    pub image_variants: Option<ImageVariants>,
+    pub published: Option<bool>,
+    /// `null` clears the schedule; omitted leaves it unchanged.
+    #[serde(default, deserialize_with = "deserialize_nullable_rfc3339")]
+    pub publish_at: Option<Option<OffsetDateTime>>,
+}
+
+/// Query parameters for the admin book listing.
+#[derive(Debug, Deserialize)]
+pub struct ListBooksQuery {
     pub published: Option<bool>,
+    /// One of `created_at` (default), `updated_at` or `title`.
+    pub sort: Option<String>,
+    /// `asc` or `desc` (default).
+    pub order: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -41,7 +71,14 @@ pub struct BookResponse {
     pub markdown: String,
     pub html: String,
     pub image_url: Option<String>,
+    pub image_variants: Option<ImageVariants>,
     pub published: bool,
+    /// One of `published`, `scheduled` or `draft`.
+    pub status: &'static str,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub publish_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub published_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339::option")]
     pub created_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339::option")]
@@ -60,7 +97,11 @@ impl From<Book> for BookResponse {
             markdown: b.markdown,
             html: b.html,
             image_url: b.image_url,
+            image_variants: b.image_variants.map(|v| v.0),
             published: b.published,
+            status: publish_status(b.published, b.publish_at),
+            publish_at: b.publish_at,
+            published_at: b.published_at,
             created_at: b.created_at,
             updated_at: b.updated_at,
         }
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Deserializer};
+use time::OffsetDateTime;
+
+/// Deserialize an optional, nullable RFC 3339 field for partial updates: a missing field
+/// (with `#[serde(default)]`) is `None`, an explicit `null` is `Some(None)`.
+pub fn deserialize_nullable_rfc3339<'de, D>(deserializer: D) -> Result<Option<Option<OffsetDateTime>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(with = "time::serde::rfc3339::option")] Option<OffsetDateTime>);
+
+    Wrapper::deserialize(deserializer).map(|w| Some(w.0))
+}
+
+/// Publication state shown to admins.
+pub fn publish_status(published: bool, publish_at: Option<OffsetDateTime>) -> &'static str {
+    match (published, publish_at) {
+        (true, _) => "published",
+        (false, Some(_)) => "scheduled",
+        (false, None) => "draft",
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageVariant {
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The renditions generated for an uploaded image, as returned by `/admin/upload-image`
+/// and stored with a book next to its `image_url`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageVariants {
+    /// The full-size image; the same URL as `image_url`.
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+    /// Every width including the full-size image, ascending.
+    pub variants: Vec<ImageVariant>,
+    pub thumbnail: ImageVariant,
+    /// Ready for `<img srcset>`: `"<url> 320w, <url> 640w, ..."`.
+    #[serde(default)]
+    pub srcset: String,
+}
+
+impl ImageVariants {
+    /// Sort the variants and rebuild `srcset` from them, so a stored value never depends
+    /// on what the client sent for it.
+    pub fn normalized(mut self) -> Self {
+        self.variants.sort_by_key(|v| v.width);
+        self.variants.dedup_by_key(|v| v.width);
+        self.srcset = self
+            .variants
+            .iter()
+            .map(|v| format!("{} {}w", v.url, v.width))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self
+    }
+
+    /// Every URL in the set, for validating a client-supplied value.
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.url.as_str())
+            .chain(self.variants.iter().map(|v| v.url.as_str()))
+            .chain(std::iter::once(self.thumbnail.url.as_str()))
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct LoginAttempt {
+    pub email: String,
+    pub ip_address: String,
+    pub succeeded: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// An email address or client IP that is currently locked out of `/login`.
+#[derive(Debug, Serialize, FromRow)]
+pub struct LoginLockout {
+    /// `email` or `ip`.
+    pub scope: String,
+    #[serde(rename = "key")]
+    pub lock_key: String,
+    pub failures: i32,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub locked_until: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginAttemptsQuery {
+    /// Only show failed attempts.
+    #[serde(default)]
+    pub failed: bool,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
@@ -2,3 +2,12 @@ pub mod user;
 pub mod session;
 pub mod article;
 pub mod book;
+pub mod image;
+pub mod tag;
+pub mod datetime;
+pub mod search;
+pub mod revision;
+pub mod preview_token;
+pub mod trash;
+pub mod login_attempt;
+pub mod api_token;
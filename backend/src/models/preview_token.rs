@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PreviewToken {
+    pub id: Vec<u8>,
+    pub article_id: Vec<u8>,
+    pub expires_at: OffsetDateTime,
+    pub revoked_at: Option<OffsetDateTime>,
+    pub created_by: Option<Vec<u8>>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePreviewTokenRequest {
+    /// Lifetime of the link in hours; defaults to 72.
+    pub expires_in_hours: Option<i64>,
+}
+
+/// Token metadata for the admin list; the signed token itself is only returned on creation.
+#[derive(Debug, Serialize)]
+pub struct PreviewTokenResponse {
+    pub id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub revoked_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+impl From<PreviewToken> for PreviewTokenResponse {
+    fn from(t: PreviewToken) -> Self {
+        let id = uuid::Uuid::from_slice(&t.id)
+            .map(|u| u.to_string())
+            .unwrap_or_default();
+        PreviewTokenResponse {
+            id,
+            expires_at: t.expires_at,
+            revoked_at: t.revoked_at,
+            created_at: t.created_at,
+        }
+    }
+}
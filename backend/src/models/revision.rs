@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+/// Snapshot of an article's content taken just before it was overwritten.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ArticleRevision {
+    pub id: Vec<u8>,
+    pub article_id: Vec<u8>,
+    pub revision_number: i32,
+    pub title: String,
+    pub markdown: String,
+    pub created_by: Option<Vec<u8>>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// Revision list entry, without the markdown body.
+#[derive(Debug, Serialize)]
+pub struct RevisionSummaryResponse {
+    pub id: String,
+    pub revision_number: i32,
+    pub title: String,
+    pub created_by: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionResponse {
+    pub id: String,
+    pub revision_number: i32,
+    pub title: String,
+    pub markdown: String,
+    pub created_by: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// `from` and `to` are revision ids; omitting `to` diffs against the article's current content.
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: String,
+    pub to: Option<String>,
+}
+
+fn uuid_string(bytes: &[u8]) -> String {
+    uuid::Uuid::from_slice(bytes)
+        .map(|u| u.to_string())
+        .unwrap_or_default()
+}
+
+impl From<ArticleRevision> for RevisionSummaryResponse {
+    fn from(r: ArticleRevision) -> Self {
+        RevisionSummaryResponse {
+            id: uuid_string(&r.id),
+            revision_number: r.revision_number,
+            title: r.title,
+            created_by: r.created_by.as_deref().map(uuid_string),
+            created_at: r.created_at,
+        }
+    }
+}
+
+impl From<ArticleRevision> for RevisionResponse {
+    fn from(r: ArticleRevision) -> Self {
+        RevisionResponse {
+            id: uuid_string(&r.id),
+            revision_number: r.revision_number,
+            title: r.title,
+            markdown: r.markdown,
+            created_by: r.created_by.as_deref().map(uuid_string),
+            created_at: r.created_at,
+        }
+    }
+}
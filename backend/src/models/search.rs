@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+/// One matching article or book, as returned by the FULLTEXT query.
+#[derive(Debug, FromRow)]
+pub struct SearchHit {
+    pub kind: String,
+    pub id: Vec<u8>,
+    pub title: String,
+    pub slug: String,
+    pub html: String,
+    pub published: bool,
+    pub score: f64,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResultResponse {
+    pub kind: String,
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    /// HTML-escaped excerpt with matched terms wrapped in `<mark>`.
+    pub snippet: String,
+    pub score: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
@@ -1,22 +1,52 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::FromRow;
 use time::OffsetDateTime;
-use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Session {
     pub id: Vec<u8>,
     pub user_id: Vec<u8>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_seen_at: Option<OffsetDateTime>,
     pub expires_at: OffsetDateTime,
     pub created_at: Option<OffsetDateTime>,
 }
 
 impl Session {
-    pub fn id_as_str(&self) -> String {
-        if let Ok(s) = std::str::from_utf8(&self.id) {
-            s.to_string()
-        } else {
-            Uuid::from_slice(&self.id).map(|u| u.to_string()).unwrap_or_default()
+    /// Identifier safe to show to clients. The real id is the cookie value, so it never
+    /// leaves the server except in `Set-Cookie`.
+    pub fn public_id(&self) -> String {
+        hex::encode(&Sha256::digest(&self.id)[..12])
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_seen_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+    /// Whether this is the session making the request.
+    pub current: bool,
+}
+
+impl From<Session> for SessionResponse {
+    fn from(s: Session) -> Self {
+        SessionResponse {
+            id: s.public_id(),
+            user_agent: s.user_agent,
+            ip_address: s.ip_address,
+            last_seen_at: s.last_seen_at,
+            expires_at: s.expires_at,
+            created_at: s.created_at,
+            current: false,
         }
     }
 }
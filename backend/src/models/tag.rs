@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Tag {
+    pub id: Vec<u8>,
+    pub name: String,
+    pub slug: String,
+}
+
+/// Tag with the number of articles it is attached to.
+#[derive(Debug, Serialize, FromRow)]
+pub struct TagWithCount {
+    pub name: String,
+    pub slug: String,
+    pub article_count: i64,
+}
+
+/// Compact tag representation embedded in article responses.
+#[derive(Debug, Serialize, Clone)]
+pub struct TagResponse {
+    pub name: String,
+    pub slug: String,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(t: Tag) -> Self {
+        TagResponse {
+            name: t.name,
+            slug: t.slug,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenameTagRequest {
+    pub name: String,
+}
+
+/// Fold every tag in `sources` into `target`, then delete the sources.
+#[derive(Debug, Deserialize)]
+pub struct MergeTagsRequest {
+    pub sources: Vec<String>,
+    pub target: String,
+}
@@ -0,0 +1,26 @@
+use serde::Serialize;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+/// A soft-deleted article or book.
+#[derive(Debug, FromRow)]
+pub struct TrashedItem {
+    pub kind: String,
+    pub id: Vec<u8>,
+    pub title: String,
+    pub slug: String,
+    pub deleted_at: OffsetDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrashedItemResponse {
+    pub kind: String,
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub deleted_at: OffsetDateTime,
+    /// When the purge job will remove the item for good.
+    #[serde(with = "time::serde::rfc3339")]
+    pub purge_at: OffsetDateTime,
+}
@@ -1,14 +1,91 @@
+use std::fmt;
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+/// What a user may do in the admin area, from most to least privileged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Everything, including permanent deletion.
+    Admin,
+    /// Edits and publishes anyone's content and manages tags.
+    Editor,
+    /// Creates content and edits only their own.
+    Author,
+    /// Read-only access to the admin area.
+    Viewer,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Editor => "editor",
+            Role::Author => "author",
+            Role::Viewer => "viewer",
+        }
+    }
+
+    pub fn can_create_content(self) -> bool {
+        matches!(self, Role::Admin | Role::Editor | Role::Author)
+    }
+
+    pub fn can_edit_any_content(self) -> bool {
+        matches!(self, Role::Admin | Role::Editor)
+    }
+
+    pub fn can_manage_tags(self) -> bool {
+        matches!(self, Role::Admin | Role::Editor)
+    }
+
+    pub fn can_purge(self) -> bool {
+        self == Role::Admin
+    }
+
+    pub fn can_view_security_log(self) -> bool {
+        self == Role::Admin
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(Role::Admin),
+            "editor" => Ok(Role::Editor),
+            "author" => Ok(Role::Author),
+            "viewer" => Ok(Role::Viewer),
+            other => Err(format!("unknown role: {}", other)),
+        }
+    }
+}
+
+impl TryFrom<String> for Role {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct User {
     pub id: Vec<u8>,
     pub email: String,
     #[serde(skip_serializing)]
     pub password_hash: String,
+    #[sqlx(try_from = "String")]
+    pub role: Role,
     pub created_at: Option<OffsetDateTime>,
 }
 
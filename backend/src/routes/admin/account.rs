@@ -0,0 +1,64 @@
+use std::net::SocketAddr;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
+    Extension,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::auth::api_tokens::delete_user_api_tokens;
+use crate::auth::session::delete_other_sessions;
+use crate::auth::throttle::{client_ip, ensure_not_throttled, record_login_attempt};
+use crate::auth::users::set_password_hash;
+use crate::error::ApiError;
+use crate::extract::Json;
+use crate::models::session::Session;
+use crate::utils::password::hash_password;
+use crate::validation::{validate_password, Validator};
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Change the signed-in user's password. Every other session is logged out and every API
+/// token revoked, as with a reset; this session stays. Wrong current passwords count
+/// towards the login lockout.
+pub async fn change_password(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    user: CurrentUser,
+    Extension(session): Extension<Session>,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let ip = client_ip(&headers, peer, state.config.trust_proxy_headers);
+    ensure_not_throttled(&state, &ip, &user.email).await?;
+    let current_valid = bcrypt::verify(&payload.current_password, &user.password_hash)
+        .map_err(|e| ApiError::internal(format!("bcrypt: {}", e)))?;
+    record_login_attempt(&state.pool, &ip, &user.email, current_valid).await?;
+
+    let mut validator = Validator::new();
+    if !current_valid {
+        validator.add("current_password", "Current password is incorrect");
+    }
+    validator.field("new_password", validate_password(&payload.new_password));
+    if !validator.has_error("new_password") && payload.new_password == payload.current_password {
+        validator.add("new_password", "New password must differ from the current one");
+    }
+    validator.finish()?;
+
+    let hash = hash_password(&payload.new_password)
+        .map_err(|e| ApiError::internal(format!("bcrypt: {}", e)))?;
+    set_password_hash(&state.pool, &user.id, &hash).await?;
+    let revoked_sessions = delete_other_sessions(&state.pool, &user.id, &session.id).await?;
+    let revoked_tokens = delete_user_api_tokens(&state.pool, &user.id).await?;
+    Ok(Json(json!({
+        "success": true,
+        "revoked_sessions": revoked_sessions,
+        "revoked_tokens": revoked_tokens,
+    })))
+}
@@ -1,78 +1,87 @@
 use axum::{
-    extract::{Path, State},
-    Json,
+    extract::State,
     http::StatusCode,
     response::IntoResponse,
 };
 use serde_json::{json, Value};
 use uuid::Uuid;
 use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use crate::content::publishing::PublishState;
+use crate::content::revisions::{lock_article, record_revision};
+use crate::content::slug_history::{record_slug_change, SlugEntity};
+use crate::content::tags::{set_article_tags, tags_for_article, tags_for_articles, validate_tags};
+use crate::content::trash::move_to_trash;
 use crate::models::article::{Article, AdminArticleResponse, CreateArticleRequest, UpdateArticleRequest};
 use crate::utils::markdown::markdown_to_html;
 use crate::utils::slug::{generate_slug, make_unique_slug};
-
-fn error_response(status: StatusCode, message: &str) -> (StatusCode, Json<Value>) {
-    (status, Json(json!({ "error": message })))
-}
+use crate::validation::{validate_markdown, validate_title, Validator};
 
 pub async fn list_articles(
     State(state): State<AppState>,
-) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+) -> Result<Json<Value>, ApiError> {
     let articles = sqlx::query_as::<_, Article>(
-        "SELECT id, title, slug, markdown, html, published, created_at, updated_at FROM articles ORDER BY created_at DESC"
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE deleted_at IS NULL ORDER BY created_at DESC"
     )
     .fetch_all(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?;
+    .await?;
 
-    let responses: Vec<AdminArticleResponse> = articles.into_iter().map(|a| a.into()).collect();
+    let ids: Vec<Vec<u8>> = articles.iter().map(|a| a.id.clone()).collect();
+    let mut tags = tags_for_articles(&state.pool, &ids).await?;
+
+    let responses: Vec<AdminArticleResponse> = articles
+        .into_iter()
+        .map(|a| {
+            let article_tags = tags.remove(&a.id).unwrap_or_default();
+            let mut response: AdminArticleResponse = a.into();
+            response.tags = article_tags;
+            response
+        })
+        .collect();
     Ok(Json(json!(responses)))
 }
 
 pub async fn get_article_by_id(
     State(state): State<AppState>,
     Path(id): Path<String>,
-) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+) -> Result<Json<Value>, ApiError> {
     let uuid = Uuid::parse_str(&id)
-        .map_err(|_| error_response(StatusCode::BAD_REQUEST, "Invalid article ID"))?;
+        .map_err(|_| ApiError::bad_request("Invalid article ID"))?;
     let id_bytes = uuid.as_bytes().to_vec();
 
     let article = sqlx::query_as::<_, Article>(
-        "SELECT id, title, slug, markdown, html, published, created_at, updated_at FROM articles WHERE id = ?"
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE id = ? AND deleted_at IS NULL"
     )
     .bind(&id_bytes)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?
-    .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Article not found"))?;
-
-    let response: AdminArticleResponse = article.into();
+    .await?
+    .ok_or_else(|| ApiError::not_found("Article not found"))?;
+
+    let article_tags = tags_for_article(&state.pool, &article.id).await?;
+
+    let mut response: AdminArticleResponse = article.into();
+    response.tags = article_tags;
     Ok(Json(json!(response)))
 }
 
 pub async fn create_article(
     State(state): State<AppState>,
+    user: CurrentUser,
     Json(payload): Json<CreateArticleRequest>,
-) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    // Validate title
-    if payload.title.is_empty() || payload.title.len() > 200 {
-        return Err(error_response(StatusCode::BAD_REQUEST, "Title must be between 1 and 200 characters"));
-    }
-    // Validate markdown
-    if payload.markdown.is_empty() {
-        return Err(error_response(StatusCode::BAD_REQUEST, "Markdown must not be empty"));
-    }
+) -> Result<impl IntoResponse, ApiError> {
+    user.require_create()?;
+    Validator::new()
+        .field("title", validate_title(&payload.title))
+        .field("markdown", validate_markdown(&payload.markdown))
+        .field("tags", validate_tags(&payload.tags))
+        .finish()?;
 
     let id = Uuid::new_v4();
     let id_bytes = id.as_bytes().to_vec();
     let html = markdown_to_html(&payload.markdown);
-    let published = payload.published.unwrap_or(false);
+    let publish = PublishState::default().apply(payload.published, Some(payload.publish_at));
 
     // Generate slug from title
     let base_slug = generate_slug(&payload.title);
@@ -84,11 +93,7 @@ pub async fn create_article(
         )
         .bind(&base_slug)
         .fetch_one(&state.pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("DB error: {}", e);
-            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-        })? > 0;
+        .await? > 0;
 
         if exists {
             make_unique_slug(&base_slug)
@@ -97,128 +102,203 @@ pub async fn create_article(
         }
     };
 
+    let mut tx = state.pool.begin().await?;
     sqlx::query(
-        "INSERT INTO articles (id, title, slug, markdown, html, published) VALUES (?, ?, ?, ?, ?, ?)"
+        "INSERT INTO articles (id, author_id, title, slug, markdown, html, published, publish_at, published_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&id_bytes)
+    .bind(&user.id)
     .bind(&payload.title)
     .bind(&slug)
     .bind(&payload.markdown)
     .bind(&html)
-    .bind(published)
-    .execute(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?;
+    .bind(publish.published)
+    .bind(publish.publish_at)
+    .bind(publish.published_at)
+    .execute(&mut *tx)
+    .await?;
+
+    set_article_tags(&mut tx, &id_bytes, &payload.tags)
+        .await?;
+    tx.commit().await?;
 
     let article = sqlx::query_as::<_, Article>(
-        "SELECT id, title, slug, markdown, html, published, created_at, updated_at FROM articles WHERE id = ?"
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE id = ? AND deleted_at IS NULL"
     )
     .bind(&id_bytes)
     .fetch_one(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?;
+    .await?;
 
-    let response: AdminArticleResponse = article.into();
+    let article_tags = tags_for_article(&state.pool, &article.id).await?;
+
+    let mut response: AdminArticleResponse = article.into();
+    response.tags = article_tags;
     Ok((StatusCode::CREATED, Json(json!(response))))
 }
 
 pub async fn update_article(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
     Json(payload): Json<UpdateArticleRequest>,
-) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+) -> Result<Json<Value>, ApiError> {
     let uuid = Uuid::parse_str(&id)
-        .map_err(|_| error_response(StatusCode::BAD_REQUEST, "Invalid article ID"))?;
+        .map_err(|_| ApiError::bad_request("Invalid article ID"))?;
     let id_bytes = uuid.as_bytes().to_vec();
+    user.require_edit(&state.pool, "articles", &id_bytes).await?;
 
-    // Validate title if provided
+    let mut validator = Validator::new();
     if let Some(ref title) = payload.title {
-        if title.is_empty() || title.len() > 200 {
-            return Err(error_response(StatusCode::BAD_REQUEST, "Title must be between 1 and 200 characters"));
-        }
+        validator.field("title", validate_title(title));
     }
-    // Validate markdown if provided
-    if let Some(ref md) = payload.markdown {
-        if md.is_empty() {
-            return Err(error_response(StatusCode::BAD_REQUEST, "Markdown must not be empty"));
-        }
+    if let Some(ref markdown) = payload.markdown {
+        validator.field("markdown", validate_markdown(markdown));
+    }
+    if let Some(ref tags) = payload.tags {
+        validator.field("tags", validate_tags(tags));
     }
+    validator.finish()?;
 
-    let article = sqlx::query_as::<_, Article>(
-        "SELECT id, title, slug, markdown, html, published, created_at, updated_at FROM articles WHERE id = ?"
-    )
-    .bind(&id_bytes)
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?
-    .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Article not found"))?;
-
-    let new_title = payload.title.unwrap_or(article.title);
+    let mut tx = state.pool.begin().await?;
+    let article = lock_article(&mut tx, &id_bytes)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Article not found"))?;
+
+    let new_title = payload.title.unwrap_or_else(|| article.title.clone());
     let has_new_markdown = payload.markdown.is_some();
     let new_markdown = payload.markdown.unwrap_or_else(|| article.markdown.clone());
+
+    // Keep the content being overwritten so the save can be undone
+    if new_title != article.title || new_markdown != article.markdown {
+        record_revision(&mut tx, &article, user.id_as_uuid())
+            .await?;
+    }
+
     let new_html = if has_new_markdown {
         markdown_to_html(&new_markdown)
     } else {
         article.html
     };
-    let new_published = payload.published.unwrap_or(article.published);
+    let publish = PublishState {
+        published: article.published,
+        publish_at: article.publish_at,
+        published_at: article.published_at,
+    }
+    .apply(payload.published, payload.publish_at);
 
     sqlx::query(
-        "UPDATE articles SET title = ?, markdown = ?, html = ?, published = ?, updated_at = NOW() WHERE id = ?"
+        "UPDATE articles SET title = ?, markdown = ?, html = ?, published = ?, publish_at = ?, published_at = ?, updated_at = NOW() WHERE id = ? AND deleted_at IS NULL"
     )
     .bind(&new_title)
     .bind(&new_markdown)
     .bind(&new_html)
-    .bind(new_published)
+    .bind(publish.published)
+    .bind(publish.publish_at)
+    .bind(publish.published_at)
     .bind(&id_bytes)
-    .execute(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?;
+    .execute(&mut *tx)
+    .await?;
+
+    if let Some(ref tags) = payload.tags {
+        set_article_tags(&mut tx, &id_bytes, tags)
+            .await?;
+    }
+    tx.commit().await?;
 
     let updated = sqlx::query_as::<_, Article>(
-        "SELECT id, title, slug, markdown, html, published, created_at, updated_at FROM articles WHERE id = ?"
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE id = ? AND deleted_at IS NULL"
     )
     .bind(&id_bytes)
     .fetch_one(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?;
+    .await?;
+
+    let article_tags = tags_for_article(&state.pool, &updated.id).await?;
 
-    let response: AdminArticleResponse = updated.into();
+    let mut response: AdminArticleResponse = updated.into();
+    response.tags = article_tags;
     Ok(Json(json!(response)))
 }
 
 pub async fn delete_article(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
-) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+) -> Result<StatusCode, ApiError> {
     let uuid = Uuid::parse_str(&id)
-        .map_err(|_| error_response(StatusCode::BAD_REQUEST, "Invalid article ID"))?;
+        .map_err(|_| ApiError::bad_request("Invalid article ID"))?;
     let id_bytes = uuid.as_bytes().to_vec();
+    user.require_edit(&state.pool, "articles", &id_bytes).await?;
 
-    sqlx::query("DELETE FROM articles WHERE id = ?")
-        .bind(&id_bytes)
-        .execute(&state.pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("DB error: {}", e);
-            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-        })?;
+    let trashed = move_to_trash(&state.pool, "articles", &id_bytes)
+        .await?;
+    if !trashed {
+        return Err(ApiError::not_found("Article not found"));
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Re-derive the slug from the current title. The previous slug keeps working as a redirect.
+pub async fn regenerate_slug(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let uuid = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("Invalid article ID"))?;
+    let id_bytes = uuid.as_bytes().to_vec();
+    user.require_edit(&state.pool, "articles", &id_bytes).await?;
+
+    let article = sqlx::query_as::<_, Article>(
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(&id_bytes)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Article not found"))?;
+
+    let base_slug = generate_slug(&article.title);
+
+    // Check for slug uniqueness against other articles; append short uuid suffix if collision
+    let slug = {
+        let exists: bool = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM articles WHERE slug = ? AND id <> ?"
+        )
+        .bind(&base_slug)
+        .bind(&id_bytes)
+        .fetch_one(&state.pool)
+        .await? > 0;
+
+        if exists {
+            make_unique_slug(&base_slug)
+        } else {
+            base_slug
+        }
+    };
+
+    if slug != article.slug {
+        let mut tx = state.pool.begin().await?;
+        sqlx::query("UPDATE articles SET slug = ?, updated_at = NOW() WHERE id = ? AND deleted_at IS NULL")
+            .bind(&slug)
+            .bind(&id_bytes)
+            .execute(&mut *tx)
+            .await?;
+
+        record_slug_change(&mut tx, SlugEntity::Article, &id_bytes, &article.slug, &slug)
+            .await?;
+        tx.commit().await?;
+    }
+
+    let updated = sqlx::query_as::<_, Article>(
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(&id_bytes)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let article_tags = tags_for_article(&state.pool, &updated.id).await?;
+
+    let mut response: AdminArticleResponse = updated.into();
+    response.tags = article_tags;
+    Ok(Json(json!(response)))
+}
@@ -1,57 +1,216 @@
-use axum::{
-    extract::{Path, State},
-    Json,
-    http::StatusCode,
-};
+use axum::extract::State;
 use serde_json::{json, Value};
+use sqlx::{MySql, MySqlPool, QueryBuilder};
 use uuid::Uuid;
 use crate::AppState;
-use crate::models::book::{Book, CreateBookRequest, UpdateBookRequest};
+use crate::auth::access::CurrentUser;
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use crate::content::publishing::PublishState;
+use crate::content::slug_history::{record_slug_change, SlugEntity};
+use crate::content::trash::move_to_trash;
+use crate::models::book::{Book, BookResponse, CreateBookRequest, ListBooksQuery, UpdateBookRequest};
 use crate::utils::markdown::markdown_to_html;
+use crate::utils::pagination::clamp_limit;
+use crate::utils::slug::{generate_slug, make_unique_slug};
+use crate::validation::{
+    validate_image_url, validate_image_variants, validate_markdown, validate_slug, validate_title, Validator,
+};
+
+/// Whether `slug` belongs to a book other than `exclude_id`. Trashed books still hold their
+/// slug until purged.
+async fn slug_in_use(pool: &MySqlPool, slug: &str, exclude_id: Option<&[u8]>) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM books WHERE slug = ? AND id <> ?")
+        .bind(slug)
+        .bind(exclude_id.unwrap_or_default())
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+/// List books including drafts, scheduled and published ones.
+pub async fn list_books(
+    State(state): State<AppState>,
+    Query(params): Query<ListBooksQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let sort_column = match params.sort.as_deref().unwrap_or("created_at") {
+        "created_at" => "created_at",
+        "updated_at" => "updated_at",
+        "title" => "title",
+        _ => return Err(ApiError::bad_request("sort must be one of created_at, updated_at, title")),
+    };
+    let order = match params.order.as_deref().unwrap_or("desc") {
+        "asc" => "ASC",
+        "desc" => "DESC",
+        _ => return Err(ApiError::bad_request("order must be asc or desc")),
+    };
+    let limit = clamp_limit(params.limit);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let mut count_query = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM books WHERE deleted_at IS NULL");
+    let mut query = QueryBuilder::<MySql>::new(
+        "SELECT id, title, slug, markdown, html, image_url, image_variants, published, publish_at, published_at, created_at, updated_at FROM books WHERE deleted_at IS NULL"
+    );
+    if let Some(published) = params.published {
+        count_query.push(" AND published = ").push_bind(published);
+        query.push(" AND published = ").push_bind(published);
+    }
+    query
+        .push(format!(" ORDER BY {} {}, id {} LIMIT ", sort_column, order, order))
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    let total: i64 = count_query
+        .build_query_scalar()
+        .fetch_one(&state.pool)
+        .await?;
+    let books = query
+        .build_query_as::<Book>()
+        .fetch_all(&state.pool)
+        .await?;
+
+    let responses: Vec<BookResponse> = books.into_iter().map(|b| b.into()).collect();
+    Ok(Json(json!({
+        "books": responses,
+        "total": total,
+        "limit": limit,
+        "offset": offset,
+    })))
+}
+
+pub async fn get_book_by_id(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| ApiError::bad_request("Invalid book ID"))?;
+    let id_bytes = uuid.as_bytes().to_vec();
+
+    let book = sqlx::query_as::<_, Book>(
+        "SELECT id, title, slug, markdown, html, image_url, image_variants, published, publish_at, published_at, created_at, updated_at FROM books WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(&id_bytes)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Book not found"))?;
+
+    let response: BookResponse = book.into();
+    Ok(Json(json!({ "book": response })))
+}
 
 pub async fn create_book(
     State(state): State<AppState>,
+    user: CurrentUser,
     Json(payload): Json<CreateBookRequest>,
-) -> Result<Json<Value>, StatusCode> {
+) -> Result<Json<Value>, ApiError> {
+    user.require_create()?;
+    let mut validator = Validator::new();
+    validator
+        .field("title", validate_title(&payload.title))
+        .field("markdown", validate_markdown(&payload.markdown));
+    if let Some(ref image_url) = payload.image_url {
+        validator.field("image_url", validate_image_url(image_url, &state.config.image_base_url()));
+    }
+    if let Some(ref variants) = payload.image_variants {
+        validator.field(
+            "image_variants",
+            validate_image_variants(variants, payload.image_url.as_deref(), &state.config.image_base_url()),
+        );
+    }
+
+    // An explicit slug must be valid and free; otherwise derive one from the title like articles do
+    let slug = match payload.slug.filter(|s| !s.is_empty()) {
+        Some(slug) => {
+            validator.field("slug", validate_slug(&slug));
+            if !validator.has_error("slug") && slug_in_use(&state.pool, &slug, None).await? {
+                validator.add("slug", "Slug is already in use");
+            }
+            slug
+        }
+        None => {
+            let base_slug = generate_slug(&payload.title);
+            if slug_in_use(&state.pool, &base_slug, None).await? {
+                make_unique_slug(&base_slug)
+            } else {
+                base_slug
+            }
+        }
+    };
+    validator.finish()?;
+
     let id = Uuid::new_v4();
     let id_bytes = id.as_bytes().to_vec();
     let html = markdown_to_html(&payload.markdown);
-    let published = payload.published.unwrap_or(false);
+    let publish = PublishState::default().apply(payload.published, Some(payload.publish_at));
+    let image_variants = payload.image_variants.map(|v| sqlx::types::Json(v.normalized()));
 
-    sqlx::query("INSERT INTO books (id, title, slug, markdown, html, image_url, published) VALUES (?, ?, ?, ?, ?, ?, ?)")
+    sqlx::query("INSERT INTO books (id, author_id, title, slug, markdown, html, image_url, image_variants, published, publish_at, published_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")
         .bind(&id_bytes)
+        .bind(&user.id)
         .bind(&payload.title)
-        .bind(&payload.slug)
+        .bind(&slug)
         .bind(&payload.markdown)
         .bind(&html)
         .bind(&payload.image_url)
-        .bind(published)
+        .bind(&image_variants)
+        .bind(publish.published)
+        .bind(publish.publish_at)
+        .bind(publish.published_at)
         .execute(&state.pool)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .await?;
 
-    Ok(Json(json!({ "id": id.to_string(), "message": "created" })))
+    Ok(Json(json!({ "id": id.to_string(), "slug": slug, "message": "created" })))
 }
 
 pub async fn update_book(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
     Json(payload): Json<UpdateBookRequest>,
-) -> Result<Json<Value>, StatusCode> {
-    let uuid = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+) -> Result<Json<Value>, ApiError> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| ApiError::bad_request("Invalid book ID"))?;
     let id_bytes = uuid.as_bytes().to_vec();
+    user.require_edit(&state.pool, "books", &id_bytes).await?;
 
     let book = sqlx::query_as::<_, Book>(
-        "SELECT id, title, slug, markdown, html, image_url, published, created_at, updated_at FROM books WHERE id = ?"
+        "SELECT id, title, slug, markdown, html, image_url, image_variants, published, publish_at, published_at, created_at, updated_at FROM books WHERE id = ? AND deleted_at IS NULL"
     )
     .bind(&id_bytes)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-    .ok_or(StatusCode::NOT_FOUND)?;
+    .await?
+    .ok_or_else(|| ApiError::not_found("Book not found"))?;
+
+    // Variants are checked against the resulting image_url, which may be the stored one
+    let image_changed = payload.image_url.is_some() && payload.image_url != book.image_url;
+    let new_image_url = payload.image_url.clone().or(book.image_url);
+
+    let mut validator = Validator::new();
+    if let Some(ref title) = payload.title {
+        validator.field("title", validate_title(title));
+    }
+    if let Some(ref markdown) = payload.markdown {
+        validator.field("markdown", validate_markdown(markdown));
+    }
+    if let Some(ref image_url) = payload.image_url {
+        validator.field("image_url", validate_image_url(image_url, &state.config.image_base_url()));
+    }
+    if let Some(ref variants) = payload.image_variants {
+        validator.field(
+            "image_variants",
+            validate_image_variants(variants, new_image_url.as_deref(), &state.config.image_base_url()),
+        );
+    }
+    if let Some(ref slug) = payload.slug {
+        validator.field("slug", validate_slug(slug));
+        if !validator.has_error("slug") && slug_in_use(&state.pool, slug, Some(&id_bytes)).await? {
+            validator.add("slug", "Slug is already in use");
+        }
+    }
+    validator.finish()?;
 
     let new_title = payload.title.unwrap_or(book.title);
-    let new_slug = payload.slug.unwrap_or(book.slug);
+    let old_slug = book.slug;
+    let new_slug = payload.slug.unwrap_or_else(|| old_slug.clone());
     let has_new_markdown = payload.markdown.is_some();
     let new_markdown = payload.markdown.unwrap_or_else(|| book.markdown.clone());
     let new_html = if has_new_markdown {
@@ -59,36 +218,54 @@ pub async fn update_book(
     } else {
         book.html
     };
-    let new_image_url = payload.image_url.or(book.image_url);
-    let new_published = payload.published.unwrap_or(book.published);
+    let new_image_variants = match payload.image_variants {
+        Some(variants) => Some(sqlx::types::Json(variants.normalized())),
+        None if image_changed => None,
+        None => book.image_variants,
+    };
+    let publish = PublishState {
+        published: book.published,
+        publish_at: book.publish_at,
+        published_at: book.published_at,
+    }
+    .apply(payload.published, payload.publish_at);
 
-    sqlx::query("UPDATE books SET title = ?, slug = ?, markdown = ?, html = ?, image_url = ?, published = ?, updated_at = NOW() WHERE id = ?")
+    let mut tx = state.pool.begin().await?;
+    sqlx::query("UPDATE books SET title = ?, slug = ?, markdown = ?, html = ?, image_url = ?, image_variants = ?, published = ?, publish_at = ?, published_at = ?, updated_at = NOW() WHERE id = ? AND deleted_at IS NULL")
         .bind(&new_title)
         .bind(&new_slug)
         .bind(&new_markdown)
         .bind(&new_html)
         .bind(&new_image_url)
-        .bind(new_published)
+        .bind(&new_image_variants)
+        .bind(publish.published)
+        .bind(publish.publish_at)
+        .bind(publish.published_at)
         .bind(&id_bytes)
-        .execute(&state.pool)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .execute(&mut *tx)
+        .await?;
+
+    record_slug_change(&mut tx, SlugEntity::Book, &id_bytes, &old_slug, &new_slug)
+        .await?;
+    tx.commit().await?;
 
     Ok(Json(json!({ "message": "updated" })))
 }
 
 pub async fn delete_book(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
-) -> Result<Json<Value>, StatusCode> {
-    let uuid = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+) -> Result<Json<Value>, ApiError> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| ApiError::bad_request("Invalid book ID"))?;
     let id_bytes = uuid.as_bytes().to_vec();
+    user.require_edit(&state.pool, "books", &id_bytes).await?;
 
-    sqlx::query("DELETE FROM books WHERE id = ?")
-        .bind(&id_bytes)
-        .execute(&state.pool)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let trashed = move_to_trash(&state.pool, "books", &id_bytes)
+        .await?;
+    if !trashed {
+        return Err(ApiError::not_found("Book not found"));
+    }
 
     Ok(Json(json!({ "message": "deleted" })))
 }
@@ -1,3 +1,13 @@
+pub mod account;
 pub mod articles;
 pub mod books;
+pub mod preview;
+pub mod revisions;
+pub mod search;
+pub mod security;
+pub mod sessions;
+pub mod tags;
+pub mod tokens;
+pub mod trash;
+pub mod two_factor;
 pub mod upload;
@@ -0,0 +1,111 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::{json, Value};
+use time::OffsetDateTime;
+use uuid::Uuid;
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use crate::auth::preview::{create_preview_token, sign_token, DEFAULT_PREVIEW_HOURS, MAX_PREVIEW_HOURS};
+use crate::models::preview_token::{CreatePreviewTokenRequest, PreviewToken, PreviewTokenResponse};
+
+async fn ensure_article_exists(state: &AppState, id_bytes: &[u8]) -> Result<(), ApiError> {
+    let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM articles WHERE id = ? AND deleted_at IS NULL")
+        .bind(id_bytes)
+        .fetch_one(&state.pool)
+        .await?
+        > 0;
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::not_found("Article not found"))
+    }
+}
+
+/// Mint a preview link for an article. The signed token is only ever returned here.
+pub async fn create_preview(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+    Json(payload): Json<CreatePreviewTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let uuid = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("Invalid article ID"))?;
+    let id_bytes = uuid.as_bytes().to_vec();
+    user.require_edit(&state.pool, "articles", &id_bytes).await?;
+
+    let hours = payload.expires_in_hours.unwrap_or(DEFAULT_PREVIEW_HOURS);
+    if !(1..=MAX_PREVIEW_HOURS).contains(&hours) {
+        return Err(ApiError::bad_request("expires_in_hours must be between 1 and 720"));
+    }
+    ensure_article_exists(&state, &id_bytes).await?;
+
+    // Whole seconds, so the expiry embedded in the token matches the stored one
+    let expires_at = OffsetDateTime::now_utc()
+        .replace_nanosecond(0)
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+        + time::Duration::hours(hours);
+    let token_id = create_preview_token(&state.pool, &id_bytes, expires_at, user.id_as_uuid())
+        .await?;
+    let token = sign_token(&state.config.session_secret, token_id, expires_at);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "id": token_id.to_string(),
+            "token": token,
+            "path": format!("/preview/{}", token),
+            "expires_at": expires_at.format(&time::format_description::well_known::Rfc3339).unwrap_or_default(),
+        })),
+    ))
+}
+
+pub async fn list_previews(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let uuid = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("Invalid article ID"))?;
+    let id_bytes = uuid.as_bytes().to_vec();
+    ensure_article_exists(&state, &id_bytes).await?;
+
+    let tokens = sqlx::query_as::<_, PreviewToken>(
+        "SELECT id, article_id, expires_at, revoked_at, created_by, created_at FROM preview_tokens \
+         WHERE article_id = ? ORDER BY created_at DESC"
+    )
+    .bind(&id_bytes)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let responses: Vec<PreviewTokenResponse> = tokens.into_iter().map(|t| t.into()).collect();
+    Ok(Json(json!({ "preview_tokens": responses })))
+}
+
+pub async fn revoke_preview(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path((id, token_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let article_uuid = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("Invalid article ID"))?;
+    user.require_edit(&state.pool, "articles", article_uuid.as_bytes()).await?;
+    let token_uuid = Uuid::parse_str(&token_id)
+        .map_err(|_| ApiError::bad_request("Invalid token ID"))?;
+
+    let result = sqlx::query(
+        "UPDATE preview_tokens SET revoked_at = COALESCE(revoked_at, NOW()) WHERE id = ? AND article_id = ?"
+    )
+    .bind(token_uuid.as_bytes().to_vec())
+    .bind(article_uuid.as_bytes().to_vec())
+    .execute(&state.pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Preview token not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,135 @@
+use axum::extract::State;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use crate::content::revisions::{get_revision, list_revisions, lock_article, record_revision};
+use crate::content::tags::tags_for_article;
+use crate::models::article::{AdminArticleResponse, Article};
+use crate::models::revision::{RevisionDiffQuery, RevisionResponse, RevisionSummaryResponse};
+use crate::utils::diff::unified_diff;
+use crate::utils::markdown::markdown_to_html;
+
+fn parse_id(id: &str, message: &str) -> Result<Vec<u8>, ApiError> {
+    Uuid::parse_str(id)
+        .map(|u| u.as_bytes().to_vec())
+        .map_err(|_| ApiError::bad_request(message))
+}
+
+async fn fetch_article(state: &AppState, id_bytes: &[u8]) -> Result<Article, ApiError> {
+    sqlx::query_as::<_, Article>(
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(id_bytes)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Article not found"))
+}
+
+pub async fn list_article_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let id_bytes = parse_id(&id, "Invalid article ID")?;
+    fetch_article(&state, &id_bytes).await?;
+
+    let revisions = list_revisions(&state.pool, &id_bytes).await?;
+    let responses: Vec<RevisionSummaryResponse> = revisions.into_iter().map(|r| r.into()).collect();
+    Ok(Json(json!({ "revisions": responses })))
+}
+
+pub async fn get_article_revision(
+    State(state): State<AppState>,
+    Path((id, revision_id)): Path<(String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    let id_bytes = parse_id(&id, "Invalid article ID")?;
+    let revision_bytes = parse_id(&revision_id, "Invalid revision ID")?;
+
+    let revision = get_revision(&state.pool, &id_bytes, &revision_bytes)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Revision not found"))?;
+
+    let response: RevisionResponse = revision.into();
+    Ok(Json(json!(response)))
+}
+
+pub async fn diff_article_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<RevisionDiffQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let id_bytes = parse_id(&id, "Invalid article ID")?;
+    let from_bytes = parse_id(&params.from, "Invalid revision ID")?;
+
+    let from = get_revision(&state.pool, &id_bytes, &from_bytes)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Revision not found"))?;
+
+    let (to_label, to_title, to_markdown) = match params.to.as_deref() {
+        Some(to) => {
+            let to_bytes = parse_id(to, "Invalid revision ID")?;
+            let to = get_revision(&state.pool, &id_bytes, &to_bytes)
+                .await?
+                .ok_or_else(|| ApiError::not_found("Revision not found"))?;
+            (format!("revision {}", to.revision_number), to.title, to.markdown)
+        }
+        None => {
+            let article = fetch_article(&state, &id_bytes).await?;
+            ("current".to_string(), article.title, article.markdown)
+        }
+    };
+
+    let diff = unified_diff(
+        &format!("revision {}", from.revision_number),
+        &from.title,
+        &from.markdown,
+        &to_label,
+        &to_title,
+        &to_markdown,
+    );
+    Ok(Json(json!({ "diff": diff })))
+}
+
+/// Bring a revision's content back as the current article; the content it replaces is kept as a new revision.
+pub async fn restore_article_revision(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path((id, revision_id)): Path<(String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    let id_bytes = parse_id(&id, "Invalid article ID")?;
+    user.require_edit(&state.pool, "articles", &id_bytes).await?;
+    let revision_bytes = parse_id(&revision_id, "Invalid revision ID")?;
+
+    let revision = get_revision(&state.pool, &id_bytes, &revision_bytes)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Revision not found"))?;
+
+    let mut tx = state.pool.begin().await?;
+    let article = lock_article(&mut tx, &id_bytes)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Article not found"))?;
+
+    if revision.title != article.title || revision.markdown != article.markdown {
+        record_revision(&mut tx, &article, user.id_as_uuid())
+            .await?;
+    }
+
+    let html = markdown_to_html(&revision.markdown);
+    sqlx::query("UPDATE articles SET title = ?, markdown = ?, html = ?, updated_at = NOW() WHERE id = ? AND deleted_at IS NULL")
+        .bind(&revision.title)
+        .bind(&revision.markdown)
+        .bind(&html)
+        .bind(&id_bytes)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    let restored = fetch_article(&state, &id_bytes).await?;
+    let article_tags = tags_for_article(&state.pool, &restored.id).await?;
+
+    let mut response: AdminArticleResponse = restored.into();
+    response.tags = article_tags;
+    Ok(Json(json!(response)))
+}
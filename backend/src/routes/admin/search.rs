@@ -0,0 +1,15 @@
+use axum::extract::State;
+use serde_json::Value;
+use crate::AppState;
+use crate::error::ApiError;
+use crate::extract::{Json, Query};
+use crate::models::search::SearchQuery;
+use crate::routes::search::run_search;
+
+/// Same as `GET /search`, but drafts are searchable and each result carries its `published` flag.
+pub async fn search(
+    State(state): State<AppState>,
+    Query(params): Query<SearchQuery>,
+) -> Result<Json<Value>, ApiError> {
+    run_search(&state, &params, true).await
+}
@@ -0,0 +1,47 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+};
+use serde_json::{json, Value};
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::auth::throttle::{active_lockouts, clear_lockout, recent_login_attempts};
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use crate::models::login_attempt::LoginAttemptsQuery;
+use crate::utils::pagination::clamp_limit;
+
+/// Recent `/login` attempts, newest first, with the email addresses and IPs locked out now.
+pub async fn list_login_attempts(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Query(params): Query<LoginAttemptsQuery>,
+) -> Result<Json<Value>, ApiError> {
+    user.require_security_log()?;
+    let limit = clamp_limit(params.limit);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let attempts = recent_login_attempts(&state.pool, params.failed, limit, offset).await?;
+    let lockouts = active_lockouts(&state.pool).await?;
+    Ok(Json(json!({
+        "attempts": attempts,
+        "lockouts": lockouts,
+        "limit": limit,
+        "offset": offset,
+    })))
+}
+
+pub async fn delete_lockout(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path((scope, key)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    user.require_security_log()?;
+    if scope != "email" && scope != "ip" {
+        return Err(ApiError::bad_request("scope must be email or ip"));
+    }
+    if !clear_lockout(&state.pool, &scope, &key).await? {
+        return Err(ApiError::not_found("Lockout not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
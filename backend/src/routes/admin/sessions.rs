@@ -0,0 +1,60 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    Extension,
+};
+use serde_json::{json, Value};
+use tower_cookies::Cookies;
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::auth::session::{clear_cookie, delete_user_session, delete_user_sessions, list_user_sessions, SESSION_COOKIE};
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use crate::models::session::{Session, SessionResponse};
+
+/// The signed-in user's active sessions, most recently used first.
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Extension(current): Extension<Session>,
+) -> Result<Json<Value>, ApiError> {
+    let current_id = current.public_id();
+    let sessions: Vec<SessionResponse> = list_user_sessions(&state.pool, &user.id)
+        .await?
+        .into_iter()
+        .map(|session| {
+            let mut response = SessionResponse::from(session);
+            response.current = response.id == current_id;
+            response
+        })
+        .collect();
+    Ok(Json(json!({ "sessions": sessions })))
+}
+
+/// Sign out one session of the current user, e.g. a browser left logged in elsewhere.
+pub async fn delete_session(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Extension(current): Extension<Session>,
+    cookies: Cookies,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if !delete_user_session(&state.pool, &user.id, &id).await? {
+        return Err(ApiError::not_found("Session not found"));
+    }
+    if id == current.public_id() {
+        clear_cookie(&cookies, SESSION_COOKIE);
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Log out everywhere, including this browser.
+pub async fn delete_all_sessions(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    cookies: Cookies,
+) -> Result<Json<Value>, ApiError> {
+    let revoked = delete_user_sessions(&state.pool, &user.id).await?;
+    clear_cookie(&cookies, SESSION_COOKIE);
+    Ok(Json(json!({ "revoked": revoked })))
+}
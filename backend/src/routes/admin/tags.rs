@@ -0,0 +1,107 @@
+use axum::extract::State;
+use serde_json::{json, Value};
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use crate::content::tags::{tag_slug, MAX_TAG_LENGTH};
+use crate::models::tag::{MergeTagsRequest, RenameTagRequest, Tag, TagResponse, TagWithCount};
+
+async fn find_tag(state: &AppState, slug: &str) -> Result<Option<Tag>, ApiError> {
+    sqlx::query_as::<_, Tag>("SELECT id, name, slug FROM tags WHERE slug = ?")
+        .bind(slug)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+/// List every tag, including those only used by drafts or not used at all.
+pub async fn list_tags(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    let tags = sqlx::query_as::<_, TagWithCount>(
+        "SELECT t.name, t.slug, COUNT(a.id) AS article_count FROM tags t \
+         LEFT JOIN article_tags at ON at.tag_id = t.id \
+         LEFT JOIN articles a ON a.id = at.article_id AND a.deleted_at IS NULL \
+         GROUP BY t.id, t.name, t.slug ORDER BY t.name"
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(json!({ "tags": tags })))
+}
+
+pub async fn rename_tag(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(slug): Path<String>,
+    Json(payload): Json<RenameTagRequest>,
+) -> Result<Json<Value>, ApiError> {
+    user.require_manage_tags()?;
+    let name = payload.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TAG_LENGTH {
+        return Err(ApiError::bad_request("Name must be between 1 and 100 characters"));
+    }
+
+    let tag = find_tag(&state, &slug)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Tag not found"))?;
+
+    let new_slug = tag_slug(name);
+    if new_slug != tag.slug && find_tag(&state, &new_slug).await?.is_some() {
+        return Err(ApiError::conflict("A tag with this name already exists; merge the tags instead"));
+    }
+
+    sqlx::query("UPDATE tags SET name = ?, slug = ? WHERE id = ?")
+        .bind(name)
+        .bind(&new_slug)
+        .bind(&tag.id)
+        .execute(&state.pool)
+        .await?;
+
+    Ok(Json(json!(TagResponse {
+        name: name.to_string(),
+        slug: new_slug,
+    })))
+}
+
+pub async fn merge_tags(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(payload): Json<MergeTagsRequest>,
+) -> Result<Json<Value>, ApiError> {
+    user.require_manage_tags()?;
+    let target = find_tag(&state, &payload.target)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Target tag not found"))?;
+
+    let mut sources = Vec::new();
+    for slug in payload.sources.iter().filter(|s| **s != target.slug) {
+        let source = find_tag(&state, slug)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Source tag not found"))?;
+        sources.push(source);
+    }
+    if sources.is_empty() {
+        return Err(ApiError::bad_request("At least one source tag is required"));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    for source in &sources {
+        sqlx::query(
+            "INSERT IGNORE INTO article_tags (article_id, tag_id) SELECT article_id, ? FROM article_tags WHERE tag_id = ?"
+        )
+        .bind(&target.id)
+        .bind(&source.id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(&source.id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "tag": TagResponse::from(target),
+        "merged": sources.into_iter().map(|s| s.slug).collect::<Vec<_>>(),
+    })))
+}
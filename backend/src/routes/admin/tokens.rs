@@ -0,0 +1,91 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::{json, Value};
+use time::OffsetDateTime;
+use uuid::Uuid;
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::auth::api_tokens::{create_api_token, delete_api_token, list_api_tokens, MAX_TOKEN_DAYS};
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use crate::models::api_token::{ApiTokenResponse, CreateApiTokenRequest, TokenScope};
+use crate::validation::Validator;
+
+const MAX_TOKEN_NAME_LENGTH: usize = 100;
+
+pub async fn list_tokens(
+    State(state): State<AppState>,
+    user: CurrentUser,
+) -> Result<Json<Value>, ApiError> {
+    let tokens: Vec<ApiTokenResponse> = list_api_tokens(&state.pool, &user.id)
+        .await?
+        .into_iter()
+        .map(ApiTokenResponse::from)
+        .collect();
+    Ok(Json(json!({ "tokens": tokens })))
+}
+
+/// Mint a personal access token. The token itself is only ever returned here.
+pub async fn create_token(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(payload): Json<CreateApiTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let name = payload.name.trim();
+    let mut validator = Validator::new();
+    let name_length = name.chars().count();
+    if name_length == 0 || name_length > MAX_TOKEN_NAME_LENGTH {
+        validator.add("name", format!("Name must be between 1 and {} characters", MAX_TOKEN_NAME_LENGTH));
+    }
+    let mut scopes: Vec<TokenScope> = Vec::new();
+    for scope in &payload.scopes {
+        match scope.parse::<TokenScope>() {
+            Ok(scope) if !scopes.contains(&scope) => scopes.push(scope),
+            Ok(_) => {}
+            Err(message) => {
+                validator.add("scopes", message);
+            }
+        }
+    }
+    if payload.scopes.is_empty() {
+        validator.add("scopes", "At least one scope is required");
+    }
+    if let Some(days) = payload.expires_in_days {
+        if !(1..=MAX_TOKEN_DAYS).contains(&days) {
+            validator.add("expires_in_days", format!("expires_in_days must be between 1 and {}", MAX_TOKEN_DAYS));
+        }
+    }
+    validator.finish()?;
+
+    let expires_at = payload
+        .expires_in_days
+        .map(|days| OffsetDateTime::now_utc() + time::Duration::days(days));
+    let (id, token) = create_api_token(&state.pool, &user.id, name, &scopes, expires_at).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "id": id.to_string(),
+            "name": name,
+            "token": token,
+            "scopes": scopes,
+            "expires_at": expires_at.and_then(|t| t.format(&time::format_description::well_known::Rfc3339).ok()),
+        })),
+    ))
+}
+
+pub async fn revoke_token(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let token_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("Invalid token ID"))?;
+    if !delete_api_token(&state.pool, &user.id, token_id).await? {
+        return Err(ApiError::not_found("Token not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
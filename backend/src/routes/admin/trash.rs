@@ -0,0 +1,107 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::content::trash::purge_trashed_book;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use crate::models::trash::{TrashedItem, TrashedItemResponse};
+
+pub async fn list_trash(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    let items = sqlx::query_as::<_, TrashedItem>(
+        "(SELECT 'article' AS kind, id, title, slug, deleted_at FROM articles WHERE deleted_at IS NOT NULL) \
+         UNION ALL (SELECT 'book' AS kind, id, title, slug, deleted_at FROM books WHERE deleted_at IS NOT NULL) \
+         ORDER BY deleted_at DESC"
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    let retention = time::Duration::days(state.config.trash_retention_days);
+    let responses: Vec<TrashedItemResponse> = items
+        .into_iter()
+        .map(|item| TrashedItemResponse {
+            id: Uuid::from_slice(&item.id)
+                .map(|u| u.to_string())
+                .unwrap_or_default(),
+            kind: item.kind,
+            title: item.title,
+            slug: item.slug,
+            deleted_at: item.deleted_at,
+            purge_at: item.deleted_at + retention,
+        })
+        .collect();
+    Ok(Json(json!({ "items": responses })))
+}
+
+/// Run `sql` against articles, then books, stopping at the first table that has the trashed id.
+async fn apply_to_trashed(state: &AppState, sql: &str, id: &str) -> Result<Option<&'static str>, ApiError> {
+    let uuid = Uuid::parse_str(id)
+        .map_err(|_| ApiError::bad_request("Invalid ID"))?;
+    let id_bytes = uuid.as_bytes().to_vec();
+
+    for (table, kind) in [("articles", "article"), ("books", "book")] {
+        let result = sqlx::query(&sql.replace("{table}", table))
+            .bind(&id_bytes)
+            .execute(&state.pool)
+            .await?;
+        if result.rows_affected() > 0 {
+            return Ok(Some(kind));
+        }
+    }
+    Ok(None)
+}
+
+pub async fn restore_from_trash(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    if let Ok(uuid) = Uuid::parse_str(&id) {
+        for table in ["articles", "books"] {
+            user.require_edit(&state.pool, table, uuid.as_bytes()).await?;
+        }
+    }
+    let kind = apply_to_trashed(
+        &state,
+        "UPDATE {table} SET deleted_at = NULL, updated_at = updated_at WHERE id = ? AND deleted_at IS NOT NULL",
+        &id,
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found("Item not found in trash"))?;
+
+    Ok(Json(json!({ "message": "restored", "kind": kind, "id": id })))
+}
+
+pub async fn purge_from_trash(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    user.require_purge()?;
+    let id_bytes = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("Invalid ID"))?
+        .as_bytes()
+        .to_vec();
+
+    let purged = sqlx::query("DELETE FROM articles WHERE id = ? AND deleted_at IS NOT NULL")
+        .bind(&id_bytes)
+        .execute(&state.pool)
+        .await?
+        .rows_affected()
+        > 0
+        || purge_trashed_book(&state.pool, state.storage.as_ref(), &id_bytes).await?;
+    if !purged {
+        return Err(ApiError::not_found("Item not found in trash"));
+    }
+
+    sqlx::query("DELETE FROM slug_history WHERE entity_id = ?")
+        .bind(&id_bytes)
+        .execute(&state.pool)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,134 @@
+use std::net::SocketAddr;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::auth::two_factor::{
+    disable_two_factor, enable_two_factor, remaining_recovery_codes, replace_recovery_codes, start_enrollment,
+    two_factor_state, verify_second_factor,
+};
+use crate::auth::throttle::{client_ip, ensure_not_throttled, record_login_attempt};
+use crate::error::ApiError;
+use crate::extract::Json;
+use crate::utils::totp;
+
+#[derive(Debug, Deserialize)]
+pub struct EnableTwoFactorRequest {
+    pub code: String,
+}
+
+/// Sensitive changes ask for the password again plus a current TOTP or recovery code.
+#[derive(Debug, Deserialize)]
+pub struct ReauthenticateRequest {
+    pub password: String,
+    pub code: String,
+}
+
+/// Wrong passwords and codes count as failed logins, so they share the login lockout.
+async fn reauthenticate(
+    state: &AppState,
+    ip: &str,
+    user: &CurrentUser,
+    payload: &ReauthenticateRequest,
+) -> Result<(), ApiError> {
+    ensure_not_throttled(state, ip, &user.email).await?;
+    let valid = bcrypt::verify(&payload.password, &user.password_hash)
+        .map_err(|e| ApiError::internal(format!("bcrypt: {}", e)))?;
+    if !valid {
+        record_login_attempt(&state.pool, ip, &user.email, false).await?;
+        return Err(ApiError::unauthorized("invalid credentials"));
+    }
+    let two_factor = two_factor_state(&state.pool, &user.id).await?;
+    if !two_factor.is_enabled() {
+        return Err(ApiError::bad_request("Two-factor authentication is not enabled"));
+    }
+    let code_valid = verify_second_factor(&state.pool, &user.id, &two_factor, &payload.code).await?;
+    record_login_attempt(&state.pool, ip, &user.email, code_valid).await?;
+    if !code_valid {
+        return Err(ApiError::unauthorized("invalid code"));
+    }
+    Ok(())
+}
+
+pub async fn get_status(
+    State(state): State<AppState>,
+    user: CurrentUser,
+) -> Result<Json<Value>, ApiError> {
+    let two_factor = two_factor_state(&state.pool, &user.id).await?;
+    let remaining = if two_factor.is_enabled() {
+        remaining_recovery_codes(&state.pool, &user.id).await?
+    } else {
+        0
+    };
+    Ok(Json(json!({
+        "enabled": two_factor.is_enabled(),
+        "recovery_codes_remaining": remaining,
+    })))
+}
+
+/// Generate a secret to scan into an authenticator app. 2FA stays off until `enable`
+/// confirms a code from it.
+pub async fn setup(
+    State(state): State<AppState>,
+    user: CurrentUser,
+) -> Result<Json<Value>, ApiError> {
+    if two_factor_state(&state.pool, &user.id).await?.is_enabled() {
+        return Err(ApiError::conflict("Two-factor authentication is already enabled"));
+    }
+    let secret = start_enrollment(&state.pool, &user.id).await?;
+    Ok(Json(json!({
+        "secret": secret,
+        "otpauth_uri": totp::provisioning_uri(&state.config.site_title, &user.email, &secret),
+    })))
+}
+
+/// Confirm enrollment with a first code. The recovery codes are only ever returned here
+/// and from `regenerate_recovery_codes`.
+pub async fn enable(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(payload): Json<EnableTwoFactorRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let two_factor = two_factor_state(&state.pool, &user.id).await?;
+    if two_factor.is_enabled() {
+        return Err(ApiError::conflict("Two-factor authentication is already enabled"));
+    }
+    let secret = two_factor
+        .totp_secret
+        .ok_or_else(|| ApiError::bad_request("Start setup first"))?;
+    let step = totp::verify(&secret, &payload.code, time::OffsetDateTime::now_utc().unix_timestamp())
+        .ok_or_else(|| ApiError::bad_request("Invalid code"))?;
+
+    let recovery_codes = enable_two_factor(&state.pool, &user.id, step).await?;
+    Ok(Json(json!({ "enabled": true, "recovery_codes": recovery_codes })))
+}
+
+pub async fn disable(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    user: CurrentUser,
+    Json(payload): Json<ReauthenticateRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let ip = client_ip(&headers, peer, state.config.trust_proxy_headers);
+    reauthenticate(&state, &ip, &user, &payload).await?;
+    disable_two_factor(&state.pool, &user.id).await?;
+    Ok(Json(json!({ "enabled": false })))
+}
+
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    user: CurrentUser,
+    Json(payload): Json<ReauthenticateRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let ip = client_ip(&headers, peer, state.config.trust_proxy_headers);
+    reauthenticate(&state, &ip, &user, &payload).await?;
+    let recovery_codes = replace_recovery_codes(&state.pool, &user.id).await?;
+    Ok(Json(json!({ "recovery_codes": recovery_codes })))
+}
@@ -1,53 +1,117 @@
 use axum::{
-    extract::{State, Multipart},
-    Json,
+    extract::{multipart::MultipartError, State, Multipart},
     http::StatusCode,
 };
-use serde_json::{json, Value};
 use uuid::Uuid;
-use aws_sdk_s3::primitives::ByteStream;
 use crate::AppState;
+use crate::auth::access::CurrentUser;
+use crate::error::ApiError;
+use crate::extract::Json;
+use crate::models::image::{ImageVariant, ImageVariants};
+use crate::utils::image::{process_upload, EncodedImage, ImageError, ImageLimits, ProcessedImage};
 
+/// Multipart framing allowed on top of `MAX_UPLOAD_BYTES` by the route's body limit.
+pub const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
+fn multipart_error(e: MultipartError) -> ApiError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        ApiError::PayloadTooLarge("Upload exceeds the size limit".to_string())
+    } else {
+        ApiError::bad_request(e.body_text())
+    }
+}
+
+async fn store(
+    state: &AppState,
+    written: &mut Vec<String>,
+    key: String,
+    image: EncodedImage,
+) -> Result<ImageVariant, ApiError> {
+    state.storage.put(&key, image.webp.into(), "image/webp").await?;
+    let url = state.storage.public_url(&key);
+    written.push(key);
+    Ok(ImageVariant {
+        url,
+        width: image.width,
+        height: image.height,
+    })
+}
+
+/// Write every rendition under `books/<uuid>`, recording each key in `written` as it lands.
+async fn store_all(
+    state: &AppState,
+    written: &mut Vec<String>,
+    processed: ProcessedImage,
+) -> Result<ImageVariants, ApiError> {
+    let stem = format!("books/{}", Uuid::new_v4());
+    let mut variants = Vec::with_capacity(processed.variants.len() + 1);
+    for variant in processed.variants {
+        let key = format!("{}-{}w.webp", stem, variant.width);
+        variants.push(store(state, written, key, variant).await?);
+    }
+    let thumbnail = store(state, written, format!("{}-thumb.webp", stem), processed.thumbnail).await?;
+    let original = store(state, written, format!("{}.webp", stem), processed.original).await?;
+    variants.push(original.clone());
+
+    let images = ImageVariants {
+        url: original.url,
+        width: original.width,
+        height: original.height,
+        variants,
+        thumbnail,
+        srcset: String::new(),
+    };
+    Ok(images.normalized())
+}
+
+/// Accept a JPEG, PNG, GIF, AVIF or WebP image and store it re-encoded as WebP, together
+/// with narrower renditions and a square thumbnail. The format is detected from the file
+/// contents; the declared content type is ignored.
 pub async fn upload_image(
     State(state): State<AppState>,
+    user: CurrentUser,
     mut multipart: Multipart,
-) -> Result<Json<Value>, StatusCode> {
-    while let Some(field) = multipart.next_field().await.map_err(|_| StatusCode::BAD_REQUEST)? {
-        let name = field.name().unwrap_or("").to_string();
-        if name == "file" {
-            let content_type = field
-                .content_type()
-                .map(|ct| ct.to_string())
-                .unwrap_or_default();
-            if content_type.as_str() != "image/webp" {
-                return Err(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+) -> Result<Json<ImageVariants>, ApiError> {
+    user.require_create()?;
+    let limits = ImageLimits {
+        max_bytes: state.config.max_upload_bytes,
+        max_pixels: state.config.max_image_pixels,
+    };
+
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        // Stop reading as soon as the limit is passed instead of buffering the whole body
+        let mut data = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            if data.len() + chunk.len() > limits.max_bytes {
+                return Err(ImageError::TooLarge {
+                    size: data.len() + chunk.len(),
+                    max: limits.max_bytes,
+                }
+                .into());
             }
+            data.extend_from_slice(&chunk);
+        }
 
-            let data = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?;
-            let filename = format!("books/{}.webp", Uuid::new_v4());
-
-            state.s3_client
-                .put_object()
-                .bucket(&state.config.aws_s3_bucket)
-                .key(&filename)
-                .body(ByteStream::from(data))
-                .content_type("image/webp")
-                .send()
-                .await
-                .map_err(|e| {
-                    tracing::error!("S3 upload error: {}", e);
-                    StatusCode::INTERNAL_SERVER_ERROR
-                })?;
-
-            let url = format!(
-                "https://{}.s3.{}.amazonaws.com/{}",
-                state.config.aws_s3_bucket,
-                state.config.aws_region,
-                filename
-            );
-
-            return Ok(Json(json!({ "url": url })));
+        // Decoding and encoding are CPU-bound; keep them off the async workers
+        let processed = tokio::task::spawn_blocking(move || process_upload(&data, limits))
+            .await
+            .map_err(|e| ApiError::internal(format!("transcode task: {}", e)))??;
+
+        let mut written = Vec::new();
+        let result = store_all(&state, &mut written, processed).await;
+        if result.is_err() {
+            // Nothing refers to a partial set; take back what was already written
+            for key in &written {
+                if let Err(e) = state.storage.delete(key).await {
+                    tracing::warn!("Failed to remove {} after a failed upload: {}", key, e);
+                }
+            }
         }
+        return result.map(Json);
     }
-    Err(StatusCode::BAD_REQUEST)
+    Err(ApiError::bad_request("Missing multipart field \"file\""))
 }
@@ -1,48 +1,147 @@
 use axum::{
-    extract::{Path, State},
-    Json,
-    http::StatusCode,
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
 };
 use serde_json::{json, Value};
+use sqlx::{MySql, QueryBuilder};
 use crate::AppState;
-use crate::models::article::{Article, PublicArticleListResponse, PublicArticleDetailResponse};
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use crate::content::slug_history::{find_slug_owner, SlugEntity};
+use crate::content::tags::{tags_for_article, tags_for_articles};
+use crate::models::article::{Article, ListArticlesQuery, PublicArticleListResponse, PublicArticleDetailResponse};
+use crate::utils::pagination::{clamp_limit, Cursor};
+use crate::utils::sitemap::encode_path_segment;
 
-fn error_response(status: StatusCode, message: &str) -> (StatusCode, Json<Value>) {
-    (status, Json(json!({ "error": message })))
+/// Permanent redirect from a retired slug; the body names the canonical slug for clients
+/// that fetch with `redirect: "manual"`.
+pub(crate) fn slug_redirect(section: &str, slug: &str) -> Response {
+    let location = format!("/{}/{}", section, encode_path_segment(slug));
+    (
+        StatusCode::MOVED_PERMANENTLY,
+        [(header::LOCATION, location.clone())],
+        Json(json!({ "redirect": true, "slug": slug, "location": location })),
+    )
+        .into_response()
 }
 
-pub async fn list_articles(State(state): State<AppState>) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    let articles = sqlx::query_as::<_, Article>(
-        "SELECT id, title, slug, markdown, html, published, created_at, updated_at FROM articles WHERE published = true ORDER BY created_at DESC"
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?;
-
-    let responses: Vec<PublicArticleListResponse> = articles.into_iter().map(|a| a.into()).collect();
-    Ok(Json(json!({ "articles": responses })))
+pub async fn list_articles(
+    State(state): State<AppState>,
+    Query(params): Query<ListArticlesQuery>,
+) -> Result<Json<Value>, ApiError> {
+    list_published_page(&state, &params, None).await
+}
+
+/// Build one page of the published article listing, optionally restricted to a tag slug.
+pub(crate) async fn list_published_page(
+    state: &AppState,
+    params: &ListArticlesQuery,
+    tag_slug: Option<&str>,
+) -> Result<Json<Value>, ApiError> {
+    let limit = clamp_limit(params.limit);
+    let cursor = match params.cursor.as_deref() {
+        Some(c) => Some(
+            Cursor::decode(c).ok_or_else(|| ApiError::bad_request("Invalid cursor"))?,
+        ),
+        None => None,
+    };
+
+    let mut query = QueryBuilder::<MySql>::new(
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE deleted_at IS NULL AND (published = true OR publish_at <= NOW())"
+    );
+    if let Some(tag_slug) = tag_slug {
+        query
+            .push(" AND id IN (SELECT at.article_id FROM article_tags at JOIN tags t ON t.id = at.tag_id WHERE t.slug = ")
+            .push_bind(tag_slug.to_string())
+            .push(")");
+    }
+    if let Some(from) = params.from {
+        query.push(" AND created_at >= ").push_bind(from);
+    }
+    if let Some(to) = params.to {
+        query.push(" AND created_at < ").push_bind(to);
+    }
+    if let Some(cursor) = cursor {
+        query
+            .push(" AND (created_at < ")
+            .push_bind(cursor.created_at)
+            .push(" OR (created_at = ")
+            .push_bind(cursor.created_at)
+            .push(" AND id < ")
+            .push_bind(cursor.id)
+            .push("))");
+    }
+    // Fetch one extra row to learn whether another page exists
+    query.push(" ORDER BY created_at DESC, id DESC LIMIT ").push_bind(limit + 1);
+
+    let mut articles = query
+        .build_query_as::<Article>()
+        .fetch_all(&state.pool)
+        .await?;
+
+    let has_more = articles.len() as i64 > limit;
+    articles.truncate(limit as usize);
+    let next_cursor = if has_more {
+        articles.last().map(|a| Cursor::new(a.created_at, &a.id).encode())
+    } else {
+        None
+    };
+
+    let ids: Vec<Vec<u8>> = articles.iter().map(|a| a.id.clone()).collect();
+    let mut tags = tags_for_articles(&state.pool, &ids).await?;
+
+    let responses: Vec<PublicArticleListResponse> = articles
+        .into_iter()
+        .map(|a| {
+            let article_tags = tags.remove(&a.id).unwrap_or_default();
+            let mut response: PublicArticleListResponse = a.into();
+            response.tags = article_tags;
+            response
+        })
+        .collect();
+    Ok(Json(json!({
+        "articles": responses,
+        "next_cursor": next_cursor,
+        "has_more": has_more,
+    })))
 }
 
 pub async fn get_article(
     State(state): State<AppState>,
     Path(slug): Path<String>,
-) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+) -> Result<Response, ApiError> {
     let article = sqlx::query_as::<_, Article>(
-        "SELECT id, title, slug, markdown, html, published, created_at, updated_at FROM articles WHERE slug = ? AND published = true"
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE slug = ? AND deleted_at IS NULL AND (published = true OR publish_at <= NOW())"
     )
     .bind(&slug)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("DB error: {}", e);
-        error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
-    })?
-    .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Article not found"))?;
-
-    let response: PublicArticleDetailResponse = article.into();
-    Ok(Json(json!(response)))
-}
+    .await?;
 
+    let article = match article {
+        Some(article) => article,
+        None => {
+            // The slug may have been renamed; point the client at the current one
+            let owner = find_slug_owner(&state.pool, SlugEntity::Article, &slug)
+                .await?;
+            if let Some(owner) = owner {
+                let current = sqlx::query_scalar::<_, String>(
+                    "SELECT slug FROM articles WHERE id = ? AND deleted_at IS NULL AND (published = true OR publish_at <= NOW())"
+                )
+                .bind(&owner)
+                .fetch_optional(&state.pool)
+                .await?;
+                if let Some(current) = current {
+                    return Ok(slug_redirect("articles", &current));
+                }
+            }
+            return Err(ApiError::not_found("Article not found"));
+        }
+    };
+
+    let article_tags = tags_for_article(&state.pool, &article.id).await?;
+
+    let mut response: PublicArticleDetailResponse = article.into();
+    response.tags = article_tags;
+    Ok(Json(json!(response)).into_response())
+}
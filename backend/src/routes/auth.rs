@@ -1,22 +1,25 @@
+use std::net::SocketAddr;
 use axum::{
-    extract::State,
-    Json,
-    http::StatusCode,
+    extract::{ConnectInfo, State},
+    http::{header::USER_AGENT, HeaderMap},
 };
 use serde::Deserialize;
 use serde_json::{json, Value};
-use tower_cookies::{Cookie, Cookies};
-use time::Duration;
+use tower_cookies::Cookies;
+use time::{Duration, OffsetDateTime};
 use crate::AppState;
-use crate::auth::session::{create_session, delete_session, get_user_by_session};
-use crate::config::SESSION_DURATION_DAYS;
-use crate::models::user::User;
-
-type AuthError = (StatusCode, Json<Value>);
+use crate::error::ApiError;
+use crate::extract::Json;
+use crate::auth::csrf::{issue_token, CSRF_SEED_COOKIE};
+use crate::auth::throttle::{client_ip, ensure_not_throttled, record_login_attempt};
+use crate::auth::session::{auth_cookie, clear_cookie, create_session, delete_session, find_session, SESSION_COOKIE};
+use crate::auth::two_factor::{
+    create_pending_login, delete_pending_login, find_pending_login, record_failed_attempt, two_factor_state,
+    verify_second_factor, PENDING_LOGIN_MINUTES,
+};
+use crate::auth::users::find_user_by_email;
 
-fn err(code: StatusCode, msg: &str) -> AuthError {
-    (code, Json(json!({ "error": msg })))
-}
+const PENDING_LOGIN_COOKIE: &str = "pending_login";
 
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
@@ -24,76 +27,145 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SecondFactorRequest {
+    /// A 6-digit TOTP code or one of the recovery codes.
+    pub code: String,
+}
+
+/// Issue the real session once every required factor has been checked.
+async fn start_session(
+    state: &AppState,
+    cookies: &Cookies,
+    headers: &HeaderMap,
+    ip: &str,
+    user_id: &[u8],
+) -> Result<Json<Value>, ApiError> {
+    let user_uuid = uuid::Uuid::from_slice(user_id)
+        .map_err(|_| ApiError::internal("malformed user id"))?;
+    let user_agent = headers.get(USER_AGENT).and_then(|v| v.to_str().ok());
+    let (session_id, expires_at) = create_session(&state.pool, &state.config, user_uuid, user_agent, ip).await?;
+    cookies.add(auth_cookie(
+        &state.config,
+        SESSION_COOKIE,
+        session_id.to_string(),
+        expires_at - OffsetDateTime::now_utc(),
+    ));
+    // Tokens issued before sign-in were bound to the seed; the session replaces it
+    clear_cookie(cookies, CSRF_SEED_COOKIE);
+    Ok(Json(json!({ "success": true })))
+}
+
 pub async fn login(
     State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     cookies: Cookies,
     Json(payload): Json<LoginRequest>,
-) -> Result<Json<Value>, AuthError> {
-    let user = sqlx::query_as::<_, User>(
-        "SELECT id, email, password_hash, created_at FROM users WHERE email = ?"
-    )
-    .bind(&payload.email)
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|_| err(StatusCode::INTERNAL_SERVER_ERROR, "internal server error"))?
-    .ok_or_else(|| err(StatusCode::UNAUTHORIZED, "invalid credentials"))?;
-
-    let valid = bcrypt::verify(&payload.password, &user.password_hash)
-        .map_err(|_| err(StatusCode::INTERNAL_SERVER_ERROR, "internal server error"))?;
-
-    if !valid {
-        return Err(err(StatusCode::UNAUTHORIZED, "invalid credentials"));
+) -> Result<Json<Value>, ApiError> {
+    let ip = client_ip(&headers, peer, state.config.trust_proxy_headers);
+    let email = payload.email.trim().to_lowercase();
+    ensure_not_throttled(&state, &ip, &email).await?;
+
+    let user = find_user_by_email(&state.pool, &email).await?;
+    let valid = match &user {
+        Some(user) => bcrypt::verify(&payload.password, &user.password_hash)
+            .map_err(|e| ApiError::internal(format!("bcrypt: {}", e)))?,
+        None => false,
+    };
+    record_login_attempt(&state.pool, &ip, &email, valid).await?;
+    let user = match user {
+        Some(user) if valid => user,
+        _ => return Err(ApiError::unauthorized("invalid credentials")),
+    };
+
+    // With 2FA on, the password only earns a short-lived pending login for `/login/2fa`
+    if two_factor_state(&state.pool, &user.id).await?.is_enabled() {
+        let pending_id = create_pending_login(&state.pool, &user.id).await?;
+        cookies.add(auth_cookie(
+            &state.config,
+            PENDING_LOGIN_COOKIE,
+            pending_id.to_string(),
+            Duration::minutes(PENDING_LOGIN_MINUTES),
+        ));
+        return Ok(Json(json!({ "success": false, "two_factor_required": true })));
     }
 
-    let user_uuid = user.id_as_uuid()
-        .ok_or_else(|| err(StatusCode::INTERNAL_SERVER_ERROR, "internal server error"))?;
-    let session_id = create_session(&state.pool, user_uuid)
-        .await
-        .map_err(|_| err(StatusCode::INTERNAL_SERVER_ERROR, "internal server error"))?;
-
-    let mut cookie = Cookie::new("session_id", session_id.to_string());
-    cookie.set_http_only(true);
-    cookie.set_same_site(tower_cookies::cookie::SameSite::Lax);
-    cookie.set_path("/");
-    cookie.set_max_age(Duration::days(SESSION_DURATION_DAYS));
-    if state.config.is_production {
-        cookie.set_secure(true);
+    start_session(&state, &cookies, &headers, &ip, &user.id).await
+}
+
+/// Second login step: trade the pending login and a TOTP or recovery code for a session.
+pub async fn login_second_factor(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    cookies: Cookies,
+    Json(payload): Json<SecondFactorRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let pending_id = cookies
+        .get(PENDING_LOGIN_COOKIE)
+        .map(|c| c.value().to_string())
+        .ok_or_else(|| ApiError::unauthorized("login expired; sign in again"))?;
+    let user_id = find_pending_login(&state.pool, &pending_id)
+        .await?
+        .ok_or_else(|| ApiError::unauthorized("login expired; sign in again"))?;
+
+    let two_factor = two_factor_state(&state.pool, &user_id).await?;
+    if !verify_second_factor(&state.pool, &user_id, &two_factor, &payload.code).await? {
+        if !record_failed_attempt(&state.pool, &pending_id).await? {
+            clear_cookie(&cookies, PENDING_LOGIN_COOKIE);
+            return Err(ApiError::unauthorized("too many invalid codes; sign in again"));
+        }
+        return Err(ApiError::unauthorized("invalid code"));
     }
-    cookies.add(cookie);
 
-    Ok(Json(json!({ "success": true })))
+    delete_pending_login(&state.pool, &pending_id).await?;
+    clear_cookie(&cookies, PENDING_LOGIN_COOKIE);
+    let ip = client_ip(&headers, peer, state.config.trust_proxy_headers);
+    start_session(&state, &cookies, &headers, &ip, &user_id).await
 }
 
 pub async fn me(
     State(state): State<AppState>,
     cookies: Cookies,
-) -> Result<Json<Value>, AuthError> {
+) -> Result<Json<Value>, ApiError> {
     let session_id = cookies
-        .get("session_id")
+        .get(SESSION_COOKIE)
         .map(|c| c.value().to_string())
-        .ok_or_else(|| err(StatusCode::UNAUTHORIZED, "unauthorized"))?;
-    let user = get_user_by_session(&state.pool, &session_id)
+        .ok_or_else(|| ApiError::unauthorized("unauthorized"))?;
+    let (_, user) = find_session(&state.pool, &session_id)
         .await
-        .ok_or_else(|| err(StatusCode::UNAUTHORIZED, "unauthorized"))?;
+        .ok_or_else(|| ApiError::unauthorized("unauthorized"))?;
     let user_id = user.id_as_uuid()
         .map(|u| u.to_string())
-        .ok_or_else(|| err(StatusCode::INTERNAL_SERVER_ERROR, "internal server error"))?;
-    Ok(Json(json!({ "user_id": user_id, "email": user.email })))
+        .ok_or_else(|| ApiError::internal("malformed user id"))?;
+    Ok(Json(json!({
+        "user_id": user_id,
+        "email": user.email,
+        "role": user.role,
+        "csrf_token": issue_token(&state.config, &cookies),
+    })))
+}
+
+/// CSRF token to send as `X-CSRF-Token` on `/login`, `/logout` and admin mutations. Fetch a
+/// new one after logging in, since the token follows the session.
+pub async fn csrf_token(
+    State(state): State<AppState>,
+    cookies: Cookies,
+) -> Json<Value> {
+    Json(json!({ "csrf_token": issue_token(&state.config, &cookies) }))
 }
 
 pub async fn logout(
     State(state): State<AppState>,
     cookies: Cookies,
 ) -> Json<Value> {
-    if let Some(session_cookie) = cookies.get("session_id") {
+    if let Some(session_cookie) = cookies.get(SESSION_COOKIE) {
         let session_id = session_cookie.value().to_string();
         let _ = delete_session(&state.pool, &session_id).await;
     }
 
-    let mut removal = Cookie::new("session_id", "");
-    removal.set_path("/");
-    removal.set_max_age(Duration::seconds(0));
-    cookies.add(removal);
+    clear_cookie(&cookies, SESSION_COOKIE);
 
     Json(json!({ "success": true }))
 }
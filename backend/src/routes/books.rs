@@ -1,19 +1,21 @@
 use axum::{
-    extract::{Path, State},
-    Json,
-    http::StatusCode,
+    extract::State,
+    response::{IntoResponse, Response},
 };
 use serde_json::{json, Value};
 use crate::AppState;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use crate::content::slug_history::{find_slug_owner, SlugEntity};
 use crate::models::book::{Book, BookResponse};
+use crate::routes::articles::slug_redirect;
 
-pub async fn list_books(State(state): State<AppState>) -> Result<Json<Value>, StatusCode> {
+pub async fn list_books(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
     let books = sqlx::query_as::<_, Book>(
-        "SELECT id, title, slug, markdown, html, image_url, published, created_at, updated_at FROM books WHERE published = true ORDER BY created_at DESC"
+        "SELECT id, title, slug, markdown, html, image_url, image_variants, published, publish_at, published_at, created_at, updated_at FROM books WHERE deleted_at IS NULL AND (published = true OR publish_at <= NOW()) ORDER BY created_at DESC"
     )
     .fetch_all(&state.pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .await?;
 
     let responses: Vec<BookResponse> = books.into_iter().map(|b| b.into()).collect();
     Ok(Json(json!({ "books": responses })))
@@ -22,16 +24,35 @@ pub async fn list_books(State(state): State<AppState>) -> Result<Json<Value>, St
 pub async fn get_book(
     State(state): State<AppState>,
     Path(slug): Path<String>,
-) -> Result<Json<Value>, StatusCode> {
+) -> Result<Response, ApiError> {
     let book = sqlx::query_as::<_, Book>(
-        "SELECT id, title, slug, markdown, html, image_url, published, created_at, updated_at FROM books WHERE slug = ? AND published = true"
+        "SELECT id, title, slug, markdown, html, image_url, image_variants, published, publish_at, published_at, created_at, updated_at FROM books WHERE slug = ? AND deleted_at IS NULL AND (published = true OR publish_at <= NOW())"
     )
     .bind(&slug)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-    .ok_or(StatusCode::NOT_FOUND)?;
+    .await?;
+
+    let book = match book {
+        Some(book) => book,
+        None => {
+            // The slug may have been renamed; point the client at the current one
+            let owner = find_slug_owner(&state.pool, SlugEntity::Book, &slug)
+                .await?;
+            if let Some(owner) = owner {
+                let current = sqlx::query_scalar::<_, String>(
+                    "SELECT slug FROM books WHERE id = ? AND deleted_at IS NULL AND (published = true OR publish_at <= NOW())"
+                )
+                .bind(&owner)
+                .fetch_optional(&state.pool)
+                .await?;
+                if let Some(current) = current {
+                    return Ok(slug_redirect("books", &current));
+                }
+            }
+            return Err(ApiError::not_found("Book not found"));
+        }
+    };
 
     let response: BookResponse = book.into();
-    Ok(Json(json!({ "book": response })))
+    Ok(Json(json!({ "book": response })).into_response())
 }
@@ -0,0 +1,207 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use crate::AppState;
+use crate::error::ApiError;
+use crate::extract::Path;
+use crate::content::tags::tags_for_articles;
+use crate::models::article::Article;
+use crate::models::tag::Tag;
+use crate::utils::feed::{atom, http_date, json_feed, parse_http_date, rss, FeedChannel, FeedEntry};
+use crate::utils::sitemap::encode_path_segment;
+
+const FEED_ITEM_LIMIT: i64 = 20;
+
+#[derive(Clone, Copy)]
+enum FeedFormat {
+    Atom,
+    Rss,
+    Json,
+}
+
+impl FeedFormat {
+    fn file_name(self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "feed.xml",
+            FeedFormat::Rss => "rss.xml",
+            FeedFormat::Json => "feed.json",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+pub async fn atom_feed(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, ApiError> {
+    render_feed(&state, &headers, None, FeedFormat::Atom).await
+}
+
+pub async fn rss_feed(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, ApiError> {
+    render_feed(&state, &headers, None, FeedFormat::Rss).await
+}
+
+pub async fn json_feed_handler(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, ApiError> {
+    render_feed(&state, &headers, None, FeedFormat::Json).await
+}
+
+pub async fn tag_atom_feed(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    render_feed(&state, &headers, Some(slug), FeedFormat::Atom).await
+}
+
+pub async fn tag_rss_feed(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    render_feed(&state, &headers, Some(slug), FeedFormat::Rss).await
+}
+
+pub async fn tag_json_feed(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    render_feed(&state, &headers, Some(slug), FeedFormat::Json).await
+}
+
+async fn render_feed(
+    state: &AppState,
+    headers: &HeaderMap,
+    tag_slug: Option<String>,
+    format: FeedFormat,
+) -> Result<Response, ApiError> {
+    let config = &state.config;
+
+    let tag = match tag_slug {
+        Some(slug) => Some(
+            sqlx::query_as::<_, Tag>("SELECT id, name, slug FROM tags WHERE slug = ?")
+                .bind(&slug)
+                .fetch_optional(&state.pool)
+                .await?
+                .ok_or_else(|| ApiError::not_found("Tag not found"))?,
+        ),
+        None => None,
+    };
+
+    let articles = match &tag {
+        Some(tag) => sqlx::query_as::<_, Article>(
+            "SELECT a.id, a.title, a.slug, a.markdown, a.html, a.published, a.publish_at, a.published_at, a.created_at, a.updated_at FROM articles a \
+             JOIN article_tags at ON at.article_id = a.id \
+             WHERE at.tag_id = ? AND a.deleted_at IS NULL AND (a.published = true OR a.publish_at <= NOW()) ORDER BY COALESCE(a.published_at, a.publish_at, a.created_at) DESC LIMIT ?"
+        )
+        .bind(&tag.id)
+        .bind(FEED_ITEM_LIMIT),
+        None => sqlx::query_as::<_, Article>(
+            "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles \
+             WHERE deleted_at IS NULL AND (published = true OR publish_at <= NOW()) ORDER BY COALESCE(published_at, publish_at, created_at) DESC LIMIT ?"
+        )
+        .bind(FEED_ITEM_LIMIT),
+    }
+    .fetch_all(&state.pool)
+    .await?;
+
+    let ids: Vec<Vec<u8>> = articles.iter().map(|a| a.id.clone()).collect();
+    let mut tags = tags_for_articles(&state.pool, &ids).await?;
+
+    let entries: Vec<FeedEntry> = articles
+        .into_iter()
+        .map(|a| {
+            // Scheduled posts are dated by when they went out, not when they were drafted
+            let published = a
+                .published_at
+                .or(a.publish_at)
+                .or(a.created_at)
+                .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+            FeedEntry {
+                id: uuid::Uuid::from_slice(&a.id)
+                    .map(|u| u.to_string())
+                    .unwrap_or_default(),
+                url: format!("{}/articles/{}", config.site_url, encode_path_segment(&a.slug)),
+                categories: tags
+                    .remove(&a.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|t| t.name)
+                    .collect(),
+                title: a.title,
+                html: a.html,
+                published,
+                updated: a.updated_at.unwrap_or(published).max(published),
+            }
+        })
+        .collect();
+
+    // Feed timestamps carry second precision so they compare cleanly with If-Modified-Since
+    let updated = entries
+        .iter()
+        .map(|e| e.updated)
+        .max()
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH)
+        .replace_nanosecond(0)
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+
+    let (title, prefix) = match &tag {
+        Some(tag) => (format!("{} - {}", config.site_title, tag.name), format!("/tags/{}", encode_path_segment(&tag.slug))),
+        None => (config.site_title.clone(), String::new()),
+    };
+    let channel = FeedChannel {
+        title,
+        link: format!("{}/articles", config.site_url),
+        self_url: format!("{}{}/{}", config.site_url, prefix, format.file_name()),
+        updated,
+    };
+
+    let body = match format {
+        FeedFormat::Atom => atom(&channel, &entries),
+        FeedFormat::Rss => rss(&channel, &entries),
+        FeedFormat::Json => json_feed(&channel, &entries).to_string(),
+    };
+
+    let etag = format!("\"{}\"", hex::encode(&Sha256::digest(body.as_bytes())[..16]));
+    let last_modified = http_date(updated);
+
+    let not_modified = match headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        Some(if_none_match) => if_none_match.split(',').any(|candidate| {
+            let candidate = candidate.trim().trim_start_matches("W/");
+            candidate == etag || candidate == "*"
+        }),
+        None => headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_http_date)
+            .is_some_and(|since| updated <= since),
+    };
+
+    if not_modified {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::LAST_MODIFIED, last_modified)],
+        )
+            .into_response());
+    }
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::ETAG, etag),
+            (header::LAST_MODIFIED, last_modified),
+            (header::CACHE_CONTROL, "public, max-age=300".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
@@ -1,5 +1,12 @@
 pub mod health;
 pub mod articles;
 pub mod books;
+pub mod feeds;
+pub mod password_reset;
+pub mod preview;
+pub mod search;
+pub mod sitemap;
+pub mod tags;
+pub mod uploads;
 pub mod auth;
 pub mod admin;
@@ -0,0 +1,93 @@
+use std::net::SocketAddr;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::AppState;
+use crate::auth::api_tokens::delete_user_api_tokens;
+use crate::auth::password_reset::{consume_reset_token, create_reset_token, RESET_TOKEN_MINUTES};
+use crate::auth::session::delete_user_sessions;
+use crate::auth::throttle::client_ip;
+use crate::auth::users::{find_user_by_email, set_password_hash};
+use crate::error::ApiError;
+use crate::extract::Json;
+use crate::mail::Email;
+use crate::utils::password::hash_password;
+use crate::validation::{validate_password, Validator};
+
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Mail a reset link if the address belongs to a user. The answer is the same either way,
+/// so the endpoint does not reveal which addresses have accounts.
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let ip = client_ip(&headers, peer, state.config.trust_proxy_headers);
+    let email = payload.email.trim().to_lowercase();
+    // Shares the login limits so the endpoint cannot be used to flood a mailbox
+    if let Some(retry_after) = state.login_throttle.check_rate(&ip, &email) {
+        return Err(ApiError::TooManyRequests(retry_after));
+    }
+
+    if let Some(user) = find_user_by_email(&state.pool, &email).await? {
+        let token = create_reset_token(&state.pool, &user.id).await?;
+        let message = Email {
+            to: user.email.clone(),
+            subject: format!("Reset your {} password", state.config.site_title),
+            body: format!(
+                "Someone asked to reset the password for {} on {}.\n\n\
+                 Open this link within {} minutes to choose a new one:\n{}/admin/reset-password?token={}\n\n\
+                 If this wasn't you, ignore this email; your password has not changed.\n",
+                user.email,
+                state.config.site_title,
+                RESET_TOKEN_MINUTES,
+                state.config.frontend_url.trim_end_matches('/'),
+                token,
+            ),
+        };
+        // Sent in the background so response times do not depend on whether the user exists
+        let mailer = state.mailer.clone();
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(&message).await {
+                tracing::error!("Failed to send password reset mail: {}", e);
+            }
+        });
+    }
+
+    Ok(Json(json!({ "success": true })))
+}
+
+/// Set a new password with a token from `forgot_password`, log the user out everywhere and
+/// revoke their API tokens, like `backend-admin reset-password`.
+pub async fn reset_password(
+    State(state): State<AppState>,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<Json<Value>, ApiError> {
+    Validator::new()
+        .field("new_password", validate_password(&payload.new_password))
+        .finish()?;
+
+    let user_id = consume_reset_token(&state.pool, &payload.token)
+        .await?
+        .ok_or_else(|| ApiError::bad_request("Reset link is invalid or has expired"))?;
+    let hash = hash_password(&payload.new_password)
+        .map_err(|e| ApiError::internal(format!("bcrypt: {}", e)))?;
+    set_password_hash(&state.pool, &user_id, &hash).await?;
+    delete_user_sessions(&state.pool, &user_id).await?;
+    delete_user_api_tokens(&state.pool, &user_id).await?;
+    Ok(Json(json!({ "success": true })))
+}
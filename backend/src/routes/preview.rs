@@ -0,0 +1,42 @@
+use axum::{
+    extract::State,
+    http::header::HeaderName,
+};
+use serde_json::{json, Value};
+use crate::AppState;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use crate::auth::preview::{get_active_preview_token, verify_token};
+use crate::content::tags::tags_for_article;
+use crate::models::article::{Article, PublicArticleDetailResponse};
+
+/// Render a draft for whoever holds a valid preview link. Invalid, expired and revoked
+/// tokens all look the same to the caller.
+pub async fn get_preview(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<([(HeaderName, &'static str); 1], Json<Value>), ApiError> {
+    let not_found = || ApiError::not_found("Preview not found");
+
+    let token_id = verify_token(&state.config.session_secret, &token).ok_or_else(not_found)?;
+    let preview = get_active_preview_token(&state.pool, token_id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    let article = sqlx::query_as::<_, Article>(
+        "SELECT id, title, slug, markdown, html, published, publish_at, published_at, created_at, updated_at FROM articles WHERE id = ? AND deleted_at IS NULL"
+    )
+    .bind(&preview.article_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(not_found)?;
+
+    let article_tags = tags_for_article(&state.pool, &article.id).await?;
+
+    let mut response: PublicArticleDetailResponse = article.into();
+    response.tags = article_tags;
+    Ok((
+        [(HeaderName::from_static("x-robots-tag"), "noindex, nofollow")],
+        Json(json!(response)),
+    ))
+}
@@ -0,0 +1,80 @@
+use axum::extract::State;
+use serde_json::{json, Value};
+use sqlx::{MySql, QueryBuilder};
+use crate::AppState;
+use crate::error::ApiError;
+use crate::extract::{Json, Query};
+use crate::models::search::{SearchHit, SearchQuery, SearchResultResponse};
+use crate::utils::pagination::clamp_limit;
+use crate::utils::text::{highlight_snippet, search_terms, strip_html};
+
+const MAX_QUERY_LENGTH: usize = 200;
+const SNIPPET_RADIUS: usize = 80;
+
+pub async fn search(
+    State(state): State<AppState>,
+    Query(params): Query<SearchQuery>,
+) -> Result<Json<Value>, ApiError> {
+    run_search(&state, &params, false).await
+}
+
+/// Rank articles and books against `params.q`; drafts are included only for the admin variant.
+pub(crate) async fn run_search(
+    state: &AppState,
+    params: &SearchQuery,
+    include_drafts: bool,
+) -> Result<Json<Value>, ApiError> {
+    let q = params.q.trim();
+    if q.is_empty() || q.chars().count() > MAX_QUERY_LENGTH {
+        return Err(ApiError::bad_request("Query must be between 1 and 200 characters"));
+    }
+    let limit = clamp_limit(params.limit);
+
+    let mut query = QueryBuilder::<MySql>::new("");
+    for (i, (kind, table)) in [("article", "articles"), ("book", "books")].iter().enumerate() {
+        if i > 0 {
+            query.push(" UNION ALL ");
+        }
+        query
+            .push(format!(
+                "(SELECT '{}' AS kind, id, title, slug, html, published, updated_at, \
+                 CAST(MATCH(title, markdown) AGAINST (",
+                kind
+            ))
+            .push_bind(q.to_string())
+            .push(" IN NATURAL LANGUAGE MODE) AS DOUBLE) AS score FROM ")
+            .push(*table)
+            .push(" WHERE MATCH(title, markdown) AGAINST (")
+            .push_bind(q.to_string())
+            .push(" IN NATURAL LANGUAGE MODE) AND deleted_at IS NULL");
+        if !include_drafts {
+            query.push(" AND (published = true OR publish_at <= NOW())");
+        }
+        query.push(")");
+    }
+    query.push(" ORDER BY score DESC LIMIT ").push_bind(limit);
+
+    let hits = query
+        .build_query_as::<SearchHit>()
+        .fetch_all(&state.pool)
+        .await?;
+
+    let terms = search_terms(q);
+    let results: Vec<SearchResultResponse> = hits
+        .into_iter()
+        .map(|hit| SearchResultResponse {
+            id: uuid::Uuid::from_slice(&hit.id)
+                .map(|u| u.to_string())
+                .unwrap_or_default(),
+            snippet: highlight_snippet(&strip_html(&hit.html), &terms, SNIPPET_RADIUS),
+            kind: hit.kind,
+            title: hit.title,
+            slug: hit.slug,
+            score: hit.score,
+            published: include_drafts.then_some(hit.published),
+            updated_at: hit.updated_at,
+        })
+        .collect();
+
+    Ok(Json(json!({ "query": q, "results": results })))
+}
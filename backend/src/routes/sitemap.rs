@@ -0,0 +1,128 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use crate::AppState;
+use crate::error::ApiError;
+use crate::extract::Path;
+use crate::utils::sitemap::{encode_path_segment, sitemap_index, urlset, SitemapUrl, MAX_URLS_PER_SITEMAP};
+
+/// Site pages that always appear at the start of the first sitemap.
+const STATIC_PATHS: [&str; 3] = ["/", "/articles", "/books"];
+
+#[derive(FromRow)]
+struct SitemapRow {
+    section: String,
+    slug: String,
+    updated_at: Option<OffsetDateTime>,
+}
+
+fn xml_response(body: String) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+/// Total number of URLs across all sitemap pages, and the newest `updated_at` among them.
+async fn url_stats(state: &AppState) -> Result<(i64, Option<OffsetDateTime>), ApiError> {
+    let (count, lastmod): (i64, Option<OffsetDateTime>) = sqlx::query_as(
+        "SELECT CAST(COUNT(*) AS SIGNED), MAX(updated_at) FROM (\
+         SELECT updated_at FROM articles WHERE deleted_at IS NULL AND (published = true OR publish_at <= NOW()) \
+         UNION ALL SELECT updated_at FROM books WHERE deleted_at IS NULL AND (published = true OR publish_at <= NOW())) AS content"
+    )
+    .fetch_one(&state.pool)
+    .await?;
+    Ok((count + STATIC_PATHS.len() as i64, lastmod))
+}
+
+/// Build the URLs of one sitemap page (1-based). Static pages take up room on page 1 only.
+async fn page_urls(state: &AppState, page: i64) -> Result<Vec<SitemapUrl>, ApiError> {
+    let static_count = STATIC_PATHS.len() as i64;
+    let (offset, limit) = if page == 1 {
+        (0, MAX_URLS_PER_SITEMAP - static_count)
+    } else {
+        (MAX_URLS_PER_SITEMAP - static_count + (page - 2) * MAX_URLS_PER_SITEMAP, MAX_URLS_PER_SITEMAP)
+    };
+
+    let rows = sqlx::query_as::<_, SitemapRow>(
+        "(SELECT 'articles' AS section, slug, updated_at FROM articles WHERE deleted_at IS NULL AND (published = true OR publish_at <= NOW())) \
+         UNION ALL (SELECT 'books' AS section, slug, updated_at FROM books WHERE deleted_at IS NULL AND (published = true OR publish_at <= NOW())) \
+         ORDER BY section, slug LIMIT ? OFFSET ?"
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let site_url = &state.config.site_url;
+    let mut urls = Vec::with_capacity(rows.len() + STATIC_PATHS.len());
+    if page == 1 {
+        urls.extend(STATIC_PATHS.iter().map(|path| SitemapUrl {
+            loc: format!("{}{}", site_url, path),
+            lastmod: None,
+        }));
+    }
+    urls.extend(rows.into_iter().map(|row| SitemapUrl {
+        loc: format!("{}/{}/{}", site_url, row.section, encode_path_segment(&row.slug)),
+        lastmod: row.updated_at,
+    }));
+    Ok(urls)
+}
+
+/// A single `<urlset>` while everything fits, otherwise a sitemap index over `/sitemaps/:n.xml`.
+pub async fn sitemap(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let (total, lastmod) = url_stats(&state).await?;
+    if total <= MAX_URLS_PER_SITEMAP {
+        return Ok(xml_response(urlset(&page_urls(&state, 1).await?)));
+    }
+
+    let pages = (total + MAX_URLS_PER_SITEMAP - 1) / MAX_URLS_PER_SITEMAP;
+    let sitemaps: Vec<SitemapUrl> = (1..=pages)
+        .map(|page| SitemapUrl {
+            loc: format!("{}/sitemaps/{}.xml", state.config.site_url, page),
+            lastmod,
+        })
+        .collect();
+    Ok(xml_response(sitemap_index(&sitemaps)))
+}
+
+pub async fn sitemap_page(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+) -> Result<Response, ApiError> {
+    let page: i64 = file
+        .strip_suffix(".xml")
+        .and_then(|n| n.parse().ok())
+        .filter(|n| *n >= 1)
+        .ok_or_else(|| ApiError::not_found("Sitemap not found"))?;
+
+    let (total, _) = url_stats(&state).await?;
+    if (page - 1) * MAX_URLS_PER_SITEMAP >= total {
+        return Err(ApiError::not_found("Sitemap not found"));
+    }
+    Ok(xml_response(urlset(&page_urls(&state, page).await?)))
+}
+
+pub async fn robots(State(state): State<AppState>) -> Response {
+    let mut body = String::from("User-agent: *\n");
+    if state.config.robots_disallow.is_empty() {
+        body.push_str("Disallow:\n");
+    }
+    for path in &state.config.robots_disallow {
+        body.push_str(&format!("Disallow: {}\n", path));
+    }
+    body.push_str(&format!("\nSitemap: {}/sitemap.xml\n", state.config.site_url));
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
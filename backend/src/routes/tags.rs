@@ -0,0 +1,39 @@
+use axum::extract::State;
+use serde_json::{json, Value};
+use crate::AppState;
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use crate::models::article::ListArticlesQuery;
+use crate::models::tag::{Tag, TagWithCount};
+use crate::routes::articles::list_published_page;
+
+/// List tags attached to at least one published article, with their article counts.
+pub async fn list_tags(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    let tags = sqlx::query_as::<_, TagWithCount>(
+        "SELECT t.name, t.slug, COUNT(a.id) AS article_count FROM tags t \
+         JOIN article_tags at ON at.tag_id = t.id \
+         JOIN articles a ON a.id = at.article_id AND a.deleted_at IS NULL AND (a.published = true OR a.publish_at <= NOW()) \
+         GROUP BY t.id, t.name, t.slug ORDER BY article_count DESC, t.name"
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(json!({ "tags": tags })))
+}
+
+/// Paginated published articles carrying the given tag; accepts the same query as `GET /articles`.
+pub async fn list_tag_articles(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(params): Query<ListArticlesQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let tag = sqlx::query_as::<_, Tag>("SELECT id, name, slug FROM tags WHERE slug = ?")
+        .bind(&slug)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Tag not found"))?;
+
+    let Json(mut page) = list_published_page(&state, &params, Some(&tag.slug)).await?;
+    page["tag"] = json!({ "name": tag.name, "slug": tag.slug });
+    Ok(Json(page))
+}
@@ -0,0 +1,30 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+};
+use crate::AppState;
+use crate::error::ApiError;
+use crate::extract::Path;
+
+/// Serve a file from the `local` storage driver. Other drivers host their files
+/// themselves, so this answers 404 for them.
+pub async fn serve_upload(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let object = state
+        .storage
+        .get(&key)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Not found"))?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, object.content_type),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+            // Keys contain a fresh UUID per upload, so their content never changes
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+        ],
+        object.body,
+    ))
+}
@@ -0,0 +1,77 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use axum::{async_trait, body::Bytes};
+use uuid::Uuid;
+use super::{key_under, validate_key, Storage, StoredObject, StorageError};
+
+/// Files under a local directory, served by the backend at `/uploads`. Lets the whole
+/// stack run without AWS.
+pub struct LocalStorage {
+    root: PathBuf,
+    base_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(root: &str, base_url: String) -> Self {
+        LocalStorage {
+            root: PathBuf::from(root),
+            base_url,
+        }
+    }
+}
+
+/// Only raster image types are served with their real type; anything else is a download.
+fn content_type_for(key: &str) -> &'static str {
+    match key.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).as_deref() {
+        Some("webp") => "image/webp",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("avif") => "image/avif",
+        _ => "application/octet-stream",
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, body: Bytes, _content_type: &str) -> Result<(), StorageError> {
+        validate_key(key)?;
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Write to a temporary name first so readers never see a half-written file
+        let tmp = path.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+        tokio::fs::write(&tmp, &body).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<StoredObject>, StorageError> {
+        validate_key(key)?;
+        match tokio::fs::read(self.root.join(key)).await {
+            Ok(body) => Ok(Some(StoredObject {
+                body: Bytes::from(body),
+                content_type: content_type_for(key),
+            })),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        validate_key(key)?;
+        match tokio::fs::remove_file(self.root.join(key)).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+
+    fn key_for_url(&self, url: &str) -> Option<String> {
+        key_under(&self.base_url, url)
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use axum::{async_trait, body::Bytes};
+use crate::config::Config;
+
+mod local;
+mod s3;
+
+pub use self::local::LocalStorage;
+pub use self::s3::S3Storage;
+
+/// An object read back from a driver that the backend serves itself.
+#[derive(Debug)]
+pub struct StoredObject {
+    pub body: Bytes,
+    pub content_type: &'static str,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    #[error("s3: {0}")]
+    S3(String),
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where uploaded files live. Chosen once at startup with `STORAGE_DRIVER`; handlers only
+/// see the trait.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, body: Bytes, content_type: &str) -> Result<(), StorageError>;
+
+    /// Read an object for `GET /uploads/*key`. Drivers whose files are public at their own
+    /// URL keep the default and are never served by the backend.
+    async fn get(&self, _key: &str) -> Result<Option<StoredObject>, StorageError> {
+        Ok(None)
+    }
+
+    /// Remove an object. Deleting a key that does not exist is not an error.
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Absolute URL clients use to fetch `key`.
+    fn public_url(&self, key: &str) -> String;
+
+    /// The inverse of `public_url`: the key behind a URL this driver handed out, if it is one.
+    fn key_for_url(&self, url: &str) -> Option<String>;
+}
+
+fn key_under(base_url: &str, url: &str) -> Option<String> {
+    let key = url.strip_prefix(base_url)?.strip_prefix('/')?;
+    validate_key(key).ok().map(|_| key.to_string())
+}
+
+/// Keys are relative paths of plain segments, e.g. `books/<uuid>.webp`.
+pub fn validate_key(key: &str) -> Result<(), StorageError> {
+    let valid = !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+/// The driver selected by `STORAGE_DRIVER`: `s3` (the default), `minio` for any
+/// S3-compatible service at `S3_ENDPOINT`, or `local` to keep files on disk.
+pub async fn from_config(config: &Config) -> Arc<dyn Storage> {
+    match config.storage_driver.as_str() {
+        "s3" | "minio" => Arc::new(S3Storage::from_config(config).await),
+        "local" => Arc::new(LocalStorage::new(&config.local_storage_dir, config.image_base_url())),
+        other => panic!("unknown STORAGE_DRIVER: {} (expected s3, minio or local)", other),
+    }
+}
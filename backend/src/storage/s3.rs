@@ -0,0 +1,69 @@
+use aws_sdk_s3::config::Region;
+use aws_sdk_s3::primitives::ByteStream;
+use axum::{async_trait, body::Bytes};
+use crate::config::Config;
+use super::{key_under, Storage, StorageError};
+
+/// AWS S3, or an S3-compatible service such as MinIO when `S3_ENDPOINT` is set.
+pub struct S3Storage {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+    base_url: String,
+}
+
+impl S3Storage {
+    pub async fn from_config(config: &Config) -> Self {
+        let shared = aws_config::defaults(aws_config::BehaviorVersion::latest())
+            .region(Region::new(config.aws_region.clone()))
+            .load()
+            .await;
+        let mut builder = aws_sdk_s3::config::Builder::from(&shared);
+        if let Some(endpoint) = &config.s3_endpoint {
+            // MinIO and most self-hosted services only support path-style addressing
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        } else if config.storage_driver == "minio" {
+            panic!("STORAGE_DRIVER=minio requires S3_ENDPOINT");
+        }
+        S3Storage {
+            client: aws_sdk_s3::Client::from_conf(builder.build()),
+            bucket: config.aws_s3_bucket.clone(),
+            base_url: config.image_base_url(),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, body: Bytes, content_type: &str) -> Result<(), StorageError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(body))
+            .content_type(content_type)
+            .send()
+            .await
+            .map_err(|e| StorageError::S3(aws_sdk_s3::error::DisplayErrorContext(&e).to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        // S3 answers success for keys that are already gone
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| StorageError::S3(aws_sdk_s3::error::DisplayErrorContext(&e).to_string()))?;
+        Ok(())
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+
+    fn key_for_url(&self, url: &str) -> Option<String> {
+        key_under(&self.base_url, url)
+    }
+}
@@ -0,0 +1,19 @@
+use similar::TextDiff;
+
+/// Render a unified diff of two documents, with the title line included so renames show up.
+pub fn unified_diff(
+    old_label: &str,
+    old_title: &str,
+    old_markdown: &str,
+    new_label: &str,
+    new_title: &str,
+    new_markdown: &str,
+) -> String {
+    let old = format!("# {}\n\n{}\n", old_title, old_markdown.trim_end_matches('\n'));
+    let new = format!("# {}\n\n{}\n", new_title, new_markdown.trim_end_matches('\n'));
+    TextDiff::from_lines(&old, &new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_label, new_label)
+        .to_string()
+}
@@ -0,0 +1,118 @@
+use serde_json::{json, Value};
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::{OffsetDateTime, UtcOffset};
+use crate::utils::text::escape_html as escape_xml;
+
+/// Channel-level metadata shared by all feed formats.
+pub struct FeedChannel {
+    pub title: String,
+    /// Human-facing page the feed describes.
+    pub link: String,
+    /// Absolute URL of the feed document itself.
+    pub self_url: String,
+    pub updated: OffsetDateTime,
+}
+
+pub struct FeedEntry {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub html: String,
+    pub categories: Vec<String>,
+    pub published: OffsetDateTime,
+    pub updated: OffsetDateTime,
+}
+
+fn rfc3339(t: OffsetDateTime) -> String {
+    t.to_offset(UtcOffset::UTC).format(&Rfc3339).unwrap_or_default()
+}
+
+/// Format a timestamp as an HTTP-date (`Sun, 06 Nov 1994 08:49:37 GMT`).
+pub fn http_date(t: OffsetDateTime) -> String {
+    t.to_offset(UtcOffset::UTC)
+        .format(&Rfc2822)
+        .map(|s| s.replace("+0000", "GMT"))
+        .unwrap_or_default()
+}
+
+/// Parse an HTTP-date as sent in `If-Modified-Since`.
+pub fn parse_http_date(s: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(&s.trim().replace("GMT", "+0000"), &Rfc2822).ok()
+}
+
+pub fn atom(channel: &FeedChannel, entries: &[FeedEntry]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape_xml(&channel.title)));
+    xml.push_str(&format!("  <id>{}</id>\n", escape_xml(&channel.self_url)));
+    xml.push_str(&format!("  <link href=\"{}\"/>\n", escape_xml(&channel.link)));
+    xml.push_str(&format!("  <link rel=\"self\" href=\"{}\"/>\n", escape_xml(&channel.self_url)));
+    xml.push_str(&format!("  <updated>{}</updated>\n", rfc3339(channel.updated)));
+    for entry in entries {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&entry.title)));
+        xml.push_str(&format!("    <id>urn:uuid:{}</id>\n", entry.id));
+        xml.push_str(&format!("    <link href=\"{}\"/>\n", escape_xml(&entry.url)));
+        xml.push_str(&format!("    <published>{}</published>\n", rfc3339(entry.published)));
+        xml.push_str(&format!("    <updated>{}</updated>\n", rfc3339(entry.updated)));
+        for category in &entry.categories {
+            xml.push_str(&format!("    <category term=\"{}\"/>\n", escape_xml(category)));
+        }
+        xml.push_str(&format!("    <content type=\"html\">{}</content>\n", escape_xml(&entry.html)));
+        xml.push_str("  </entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+pub fn rss(channel: &FeedChannel, entries: &[FeedEntry]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape_xml(&channel.title)));
+    xml.push_str(&format!("  <link>{}</link>\n", escape_xml(&channel.link)));
+    xml.push_str(&format!("  <description>{}</description>\n", escape_xml(&channel.title)));
+    xml.push_str(&format!(
+        "  <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape_xml(&channel.self_url)
+    ));
+    xml.push_str(&format!("  <lastBuildDate>{}</lastBuildDate>\n", http_date(channel.updated)));
+    for entry in entries {
+        xml.push_str("  <item>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&entry.title)));
+        xml.push_str(&format!("    <link>{}</link>\n", escape_xml(&entry.url)));
+        xml.push_str(&format!("    <guid isPermaLink=\"false\">urn:uuid:{}</guid>\n", entry.id));
+        xml.push_str(&format!("    <pubDate>{}</pubDate>\n", http_date(entry.published)));
+        for category in &entry.categories {
+            xml.push_str(&format!("    <category>{}</category>\n", escape_xml(category)));
+        }
+        xml.push_str(&format!("    <description>{}</description>\n", escape_xml(&entry.html)));
+        xml.push_str("  </item>\n");
+    }
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+/// Render a JSON Feed 1.1 document.
+pub fn json_feed(channel: &FeedChannel, entries: &[FeedEntry]) -> Value {
+    let items: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "id": format!("urn:uuid:{}", entry.id),
+                "url": entry.url,
+                "title": entry.title,
+                "content_html": entry.html,
+                "date_published": rfc3339(entry.published),
+                "date_modified": rfc3339(entry.updated),
+                "tags": entry.categories,
+            })
+        })
+        .collect();
+    json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": channel.title,
+        "home_page_url": channel.link,
+        "feed_url": channel.self_url,
+        "items": items,
+    })
+}
@@ -0,0 +1,280 @@
+use std::io::Cursor;
+use image::{imageops::FilterType, DynamicImage, ImageReader, Limits};
+
+/// Quality passed to the lossy WebP encoder (0–100).
+pub const WEBP_QUALITY: f32 = 82.0;
+/// Widths generated for `srcset`; widths at or above the original are skipped.
+pub const VARIANT_WIDTHS: [u32; 3] = [320, 640, 1280];
+/// Edge length of the square, center-cropped thumbnail.
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// Upload formats, identified by their magic bytes rather than the declared content type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Jpeg,
+    Png,
+    Gif,
+    Avif,
+    WebP,
+}
+
+impl SourceFormat {
+    pub fn sniff(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(SourceFormat::Jpeg)
+        } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(SourceFormat::Png)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(SourceFormat::Gif)
+        } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+            Some(SourceFormat::WebP)
+        } else if is_avif(data) {
+            Some(SourceFormat::Avif)
+        } else {
+            None
+        }
+    }
+
+    fn image_format(self) -> image::ImageFormat {
+        match self {
+            SourceFormat::Jpeg => image::ImageFormat::Jpeg,
+            SourceFormat::Png => image::ImageFormat::Png,
+            SourceFormat::Gif => image::ImageFormat::Gif,
+            SourceFormat::Avif => image::ImageFormat::Avif,
+            SourceFormat::WebP => image::ImageFormat::WebP,
+        }
+    }
+}
+
+/// An ISO-BMFF `ftyp` box whose major or compatible brands include `avif` or `avis`.
+fn is_avif(data: &[u8]) -> bool {
+    if data.len() < 16 || &data[4..8] != b"ftyp" {
+        return false;
+    }
+    let box_size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let end = box_size.clamp(16, data.len());
+    // Major brand at 8..12, minor version at 12..16, then compatible brands
+    std::iter::once(&data[8..12])
+        .chain(data[16..end].chunks_exact(4))
+        .any(|brand| brand == b"avif" || brand == b"avis")
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImageError {
+    #[error("Upload is {size} bytes; the limit is {max}")]
+    TooLarge { size: usize, max: usize },
+    #[error("Image is {width}x{height} ({} pixels); the limit is {max} pixels", *width as u64 * *height as u64)]
+    TooManyPixels { width: u32, height: u32, max: u64 },
+    #[error("Unsupported image format; upload JPEG, PNG, GIF, AVIF or WebP")]
+    UnsupportedFormat,
+    #[error("AVIF uploads are not supported by this server build")]
+    AvifUnavailable,
+    #[error("Could not decode image: {0}")]
+    Decode(String),
+    #[error("Could not encode WebP: {0}")]
+    Encode(String),
+}
+
+/// Upper bounds applied before and while decoding, so a small file cannot expand into a
+/// huge bitmap.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageLimits {
+    pub max_bytes: usize,
+    pub max_pixels: u64,
+}
+
+/// Check the format and size of an upload and decode it. Animated GIFs keep their first frame.
+pub fn decode_upload(data: &[u8], limits: ImageLimits) -> Result<DynamicImage, ImageError> {
+    if data.len() > limits.max_bytes {
+        return Err(ImageError::TooLarge { size: data.len(), max: limits.max_bytes });
+    }
+    let format = SourceFormat::sniff(data).ok_or(ImageError::UnsupportedFormat)?;
+    if format == SourceFormat::Avif && !cfg!(feature = "avif") {
+        return Err(ImageError::AvifUnavailable);
+    }
+
+    let reader = |format: SourceFormat| {
+        let mut reader = ImageReader::with_format(Cursor::new(data), format.image_format());
+        let mut decoder_limits = Limits::default();
+        // RGBA8 output plus headroom for the decoder's own buffers
+        decoder_limits.max_alloc = Some(limits.max_pixels.saturating_mul(4 * 2));
+        reader.limits(decoder_limits);
+        reader
+    };
+
+    let (width, height) = reader(format)
+        .into_dimensions()
+        .map_err(|e| ImageError::Decode(e.to_string()))?;
+    if width as u64 * height as u64 > limits.max_pixels {
+        return Err(ImageError::TooManyPixels { width, height, max: limits.max_pixels });
+    }
+    reader(format).decode().map_err(|e| ImageError::Decode(e.to_string()))
+}
+
+/// Lossy WebP at `WEBP_QUALITY`. Images without transparency are encoded as RGB.
+pub fn encode_webp(image: &DynamicImage) -> Result<Vec<u8>, ImageError> {
+    let (width, height) = (image.width(), image.height());
+    let encoded = if image.color().has_alpha() {
+        let rgba = image.to_rgba8();
+        webp::Encoder::from_rgba(rgba.as_raw(), width, height)
+            .encode_simple(false, WEBP_QUALITY)
+            .map(|memory| memory.to_vec())
+    } else {
+        let rgb = image.to_rgb8();
+        webp::Encoder::from_rgb(rgb.as_raw(), width, height)
+            .encode_simple(false, WEBP_QUALITY)
+            .map(|memory| memory.to_vec())
+    };
+    encoded.map_err(|e| ImageError::Encode(format!("{:?}", e)))
+}
+
+/// One WebP rendition of an upload.
+#[derive(Debug)]
+pub struct EncodedImage {
+    pub width: u32,
+    pub height: u32,
+    pub webp: Vec<u8>,
+}
+
+impl EncodedImage {
+    fn encode(image: &DynamicImage) -> Result<Self, ImageError> {
+        Ok(EncodedImage {
+            width: image.width(),
+            height: image.height(),
+            webp: encode_webp(image)?,
+        })
+    }
+}
+
+/// Everything stored for one upload: the full-size image, smaller widths and a thumbnail.
+#[derive(Debug)]
+pub struct ProcessedImage {
+    pub original: EncodedImage,
+    /// Ascending by width, all narrower than the original.
+    pub variants: Vec<EncodedImage>,
+    pub thumbnail: EncodedImage,
+}
+
+/// Validate and decode an upload, then encode it as WebP at full size, at each of
+/// `VARIANT_WIDTHS` narrower than the original, and as a square thumbnail. Re-encoding
+/// also drops any metadata (EXIF, GPS) the original carried.
+pub fn process_upload(data: &[u8], limits: ImageLimits) -> Result<ProcessedImage, ImageError> {
+    let image = decode_upload(data, limits)?;
+    let variants = VARIANT_WIDTHS
+        .iter()
+        .filter(|&&width| width < image.width())
+        .map(|&width| EncodedImage::encode(&image.resize(width, u32::MAX, FilterType::Lanczos3)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let thumbnail = EncodedImage::encode(&image.resize_to_fill(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Lanczos3))?;
+    Ok(ProcessedImage {
+        original: EncodedImage::encode(&image)?,
+        variants,
+        thumbnail,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageFormat, RgbImage};
+    use super::*;
+
+    const LIMITS: ImageLimits = ImageLimits { max_bytes: 1 << 20, max_pixels: 1 << 20 };
+
+    fn encoded(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, image::Rgb([200, 80, 40])));
+        let mut out = Cursor::new(Vec::new());
+        image.write_to(&mut out, format).unwrap();
+        out.into_inner()
+    }
+
+    /// An `ftyp` box with the given major brand and compatible brands.
+    fn ftyp(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+        let size = 16 + 4 * compatible.len() as u32;
+        let mut data = size.to_be_bytes().to_vec();
+        data.extend_from_slice(b"ftyp");
+        data.extend_from_slice(major);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        for brand in compatible {
+            data.extend_from_slice(*brand);
+        }
+        data
+    }
+
+    #[test]
+    fn sniffs_each_format() {
+        assert_eq!(SourceFormat::sniff(&encoded(2, 2, ImageFormat::Jpeg)), Some(SourceFormat::Jpeg));
+        assert_eq!(SourceFormat::sniff(&encoded(2, 2, ImageFormat::Png)), Some(SourceFormat::Png));
+        assert_eq!(SourceFormat::sniff(&encoded(2, 2, ImageFormat::Gif)), Some(SourceFormat::Gif));
+        assert_eq!(SourceFormat::sniff(&encoded(2, 2, ImageFormat::WebP)), Some(SourceFormat::WebP));
+        assert_eq!(SourceFormat::sniff(&ftyp(b"avif", &[b"mif1", b"miaf"])), Some(SourceFormat::Avif));
+    }
+
+    #[test]
+    fn finds_avif_among_compatible_brands_only_inside_the_box() {
+        assert_eq!(SourceFormat::sniff(&ftyp(b"mif1", &[b"miaf", b"avis"])), Some(SourceFormat::Avif));
+        assert_eq!(SourceFormat::sniff(&ftyp(b"heic", &[b"mif1", b"heic"])), None);
+
+        // A brand past the end of the declared box belongs to the next box
+        let mut data = ftyp(b"heic", &[b"mif1"]);
+        data.extend_from_slice(b"avif");
+        assert_eq!(SourceFormat::sniff(&data), None);
+    }
+
+    #[test]
+    fn rejects_truncated_headers() {
+        assert_eq!(SourceFormat::sniff(b"\x89PNG\r\n"), None);
+        assert_eq!(SourceFormat::sniff(b"GIF8"), None);
+        assert_eq!(SourceFormat::sniff(b"\xFF\xD8"), None);
+        assert_eq!(SourceFormat::sniff(b"RIFF\0\0\0\0WEB"), None);
+        assert_eq!(SourceFormat::sniff(&ftyp(b"avif", &[])[..12]), None);
+        assert_eq!(SourceFormat::sniff(b""), None);
+    }
+
+    #[test]
+    fn truncated_body_fails_to_decode() {
+        let png = encoded(8, 8, ImageFormat::Png);
+        let result = decode_upload(&png[..png.len() / 2], LIMITS);
+        assert!(matches!(result, Err(ImageError::Decode(_))), "{:?}", result);
+    }
+
+    #[test]
+    fn rejects_non_images() {
+        let result = decode_upload(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>", LIMITS);
+        assert!(matches!(result, Err(ImageError::UnsupportedFormat)));
+    }
+
+    #[test]
+    fn enforces_byte_limit_before_decoding() {
+        let png = encoded(4, 4, ImageFormat::Png);
+        let limits = ImageLimits { max_bytes: png.len() - 1, ..LIMITS };
+        let result = decode_upload(&png, limits);
+        assert!(matches!(result, Err(ImageError::TooLarge { size, max }) if size == png.len() && max == png.len() - 1));
+        assert!(decode_upload(&png, ImageLimits { max_bytes: png.len(), ..LIMITS }).is_ok());
+    }
+
+    #[test]
+    fn enforces_pixel_limit_from_the_header() {
+        let png = encoded(100, 50, ImageFormat::Png);
+        let limits = ImageLimits { max_pixels: 100 * 50 - 1, ..LIMITS };
+        let result = decode_upload(&png, limits);
+        assert!(matches!(result, Err(ImageError::TooManyPixels { width: 100, height: 50, .. })));
+        assert!(decode_upload(&png, ImageLimits { max_pixels: 100 * 50, ..LIMITS }).is_ok());
+    }
+
+    #[cfg(not(feature = "avif"))]
+    #[test]
+    fn avif_needs_the_feature() {
+        let result = decode_upload(&ftyp(b"avif", &[b"mif1"]), LIMITS);
+        assert!(matches!(result, Err(ImageError::AvifUnavailable)));
+    }
+
+    #[test]
+    fn produces_narrower_variants_and_a_square_thumbnail() {
+        let processed = process_upload(&encoded(700, 350, ImageFormat::Png), LIMITS).unwrap();
+        assert_eq!((processed.original.width, processed.original.height), (700, 350));
+        let widths: Vec<(u32, u32)> = processed.variants.iter().map(|v| (v.width, v.height)).collect();
+        assert_eq!(widths, vec![(320, 160), (640, 320)]);
+        assert_eq!((processed.thumbnail.width, processed.thumbnail.height), (THUMBNAIL_SIZE, THUMBNAIL_SIZE));
+        assert_eq!(SourceFormat::sniff(&processed.thumbnail.webp), Some(SourceFormat::WebP));
+    }
+}
@@ -1,3 +1,10 @@
+pub mod diff;
+pub mod feed;
+pub mod image;
 pub mod markdown;
+pub mod pagination;
 pub mod password;
+pub mod sitemap;
 pub mod slug;
+pub mod text;
+pub mod totp;
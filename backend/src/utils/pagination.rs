@@ -0,0 +1,84 @@
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Clamp a client-supplied page size to `1..=MAX_PAGE_SIZE`.
+pub fn clamp_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Keyset position over `(created_at, id)`, encoded as `<unix seconds>_<uuid without hyphens>`.
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    pub created_at: OffsetDateTime,
+    pub id: Vec<u8>,
+}
+
+impl Cursor {
+    pub fn new(created_at: Option<OffsetDateTime>, id: &[u8]) -> Self {
+        Cursor {
+            created_at: created_at.unwrap_or(OffsetDateTime::UNIX_EPOCH),
+            id: id.to_vec(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let id = Uuid::from_slice(&self.id)
+            .map(|u| u.simple().to_string())
+            .unwrap_or_default();
+        format!("{}_{}", self.created_at.unix_timestamp(), id)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let (ts, id) = cursor.split_once('_')?;
+        let created_at = OffsetDateTime::from_unix_timestamp(ts.parse().ok()?).ok()?;
+        let id = Uuid::parse_str(id).ok()?;
+        Some(Cursor {
+            created_at,
+            id: id.as_bytes().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamps_limit() {
+        assert_eq!(clamp_limit(None), DEFAULT_PAGE_SIZE);
+        assert_eq!(clamp_limit(Some(0)), 1);
+        assert_eq!(clamp_limit(Some(-5)), 1);
+        assert_eq!(clamp_limit(Some(50)), 50);
+        assert_eq!(clamp_limit(Some(MAX_PAGE_SIZE + 1)), MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let id = Uuid::new_v4();
+        let created_at = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let encoded = Cursor::new(Some(created_at), id.as_bytes()).encode();
+        assert_eq!(encoded, format!("1700000000_{}", id.simple()));
+
+        let decoded = Cursor::decode(&encoded).unwrap();
+        assert_eq!(decoded.created_at, created_at);
+        assert_eq!(decoded.id, id.as_bytes().to_vec());
+    }
+
+    #[test]
+    fn missing_created_at_sorts_as_epoch() {
+        let id = Uuid::new_v4();
+        let decoded = Cursor::decode(&Cursor::new(None, id.as_bytes()).encode()).unwrap();
+        assert_eq!(decoded.created_at, OffsetDateTime::UNIX_EPOCH);
+    }
+
+    #[test]
+    fn rejects_malformed_cursors() {
+        let id = Uuid::new_v4().simple().to_string();
+        for cursor in [String::new(), "1700000000".to_string(), format!("abc_{}", id), "1700000000_xyz".to_string(), format!("99999999999999999_{}", id)] {
+            assert!(Cursor::decode(&cursor).is_none(), "{:?}", cursor);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+use crate::utils::text::escape_html as escape_xml;
+
+/// Maximum number of `<url>` entries a single sitemap may hold, per sitemaps.org.
+pub const MAX_URLS_PER_SITEMAP: i64 = 50_000;
+
+/// Everything but RFC 3986 unreserved characters gets escaped in a path segment.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// Percent-encode one path segment (e.g. a non-ASCII slug); `<loc>` must be a plain URL.
+pub fn encode_path_segment(segment: &str) -> String {
+    utf8_percent_encode(segment, PATH_SEGMENT).to_string()
+}
+
+pub struct SitemapUrl {
+    pub loc: String,
+    pub lastmod: Option<OffsetDateTime>,
+}
+
+fn lastmod_tag(lastmod: Option<OffsetDateTime>) -> String {
+    lastmod
+        .and_then(|t| t.to_offset(UtcOffset::UTC).format(&Rfc3339).ok())
+        .map(|t| format!("<lastmod>{}</lastmod>", t))
+        .unwrap_or_default()
+}
+
+pub fn urlset(urls: &[SitemapUrl]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for url in urls {
+        xml.push_str(&format!(
+            "  <url><loc>{}</loc>{}</url>\n",
+            escape_xml(&url.loc),
+            lastmod_tag(url.lastmod)
+        ));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+pub fn sitemap_index(sitemaps: &[SitemapUrl]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for sitemap in sitemaps {
+        xml.push_str(&format!(
+            "  <sitemap><loc>{}</loc>{}</sitemap>\n",
+            escape_xml(&sitemap.loc),
+            lastmod_tag(sitemap.lastmod)
+        ));
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
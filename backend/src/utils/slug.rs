@@ -1,6 +1,7 @@
 use uuid::Uuid;
 
-pub fn generate_slug(title: &str) -> String {
+/// Lowercase alphanumerics joined by single hyphens; empty when `title` has no alphanumerics.
+pub fn slugify(title: &str) -> String {
     let slug = title
         .to_lowercase()
         .chars()
@@ -8,12 +9,15 @@ pub fn generate_slug(title: &str) -> String {
         .collect::<String>();
 
     // Collapse consecutive hyphens and trim leading/trailing hyphens
-    let slug = slug
+    slug
         .split('-')
         .filter(|s| !s.is_empty())
         .collect::<Vec<_>>()
-        .join("-");
+        .join("-")
+}
 
+pub fn generate_slug(title: &str) -> String {
+    let slug = slugify(title);
     if slug.is_empty() {
         Uuid::new_v4().to_string()
     } else {
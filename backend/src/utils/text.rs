@@ -0,0 +1,102 @@
+/// Strip tags from sanitized HTML and collapse whitespace, decoding the entities `ammonia` emits.
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Split a search query into lowercase terms worth highlighting.
+pub fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.chars().count() >= 2)
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// Build an HTML-escaped excerpt of `text` around the first matching term, wrapping every
+/// term occurrence in `<mark>`. Falls back to the start of the text when nothing matches.
+pub fn highlight_snippet(text: &str, terms: &[String], radius: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    // One lowercase char per source char keeps indices aligned with `chars`
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let terms: Vec<Vec<char>> = terms
+        .iter()
+        .map(|t| t.chars().filter_map(|c| c.to_lowercase().next()).collect())
+        .filter(|t: &Vec<char>| !t.is_empty())
+        .collect();
+
+    let match_at = |i: usize| -> Option<usize> {
+        terms
+            .iter()
+            .filter(|t| lower[i..].starts_with(t))
+            .map(|t| t.len())
+            .max()
+    };
+
+    let first = (0..lower.len()).find(|&i| match_at(i).is_some());
+    let (start, end) = match first {
+        Some(i) => (i.saturating_sub(radius), (i + radius).min(chars.len())),
+        None => (0, (radius * 2).min(chars.len())),
+    };
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut i = start;
+    while i < end {
+        match match_at(i) {
+            Some(len) => {
+                let segment: String = chars[i..(i + len).min(chars.len())].iter().collect();
+                snippet.push_str("<mark>");
+                snippet.push_str(&escape_html(&segment));
+                snippet.push_str("</mark>");
+                i += len;
+            }
+            None => {
+                snippet.push_str(&escape_html(&chars[i].to_string()));
+                i += 1;
+            }
+        }
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
@@ -0,0 +1,133 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// RFC 6238 defaults, which is what authenticator apps assume.
+pub const STEP_SECONDS: i64 = 30;
+pub const DIGITS: u32 = 6;
+/// Codes from one step either side of now are accepted to allow for clock drift.
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+/// A fresh 160-bit secret, base32-encoded without padding.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// HOTP (RFC 4226) value of `secret` for the given counter.
+fn hotp(secret: &[u8], counter: u64) -> u32 {
+    let mut mac = HmacSha1::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+    value % 10u32.pow(DIGITS)
+}
+
+/// Check `code` against `secret` at `unix_time`, returning the matching time step so the
+/// caller can refuse to accept the same step twice.
+pub fn verify(secret: &str, code: &str, unix_time: i64) -> Option<i64> {
+    let secret = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let code = code.trim().replace(' ', "");
+    if code.len() != DIGITS as usize {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+
+    let current = unix_time / STEP_SECONDS;
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS)
+        .filter(|step| *step >= 0)
+        .find(|step| hotp(&secret, *step as u64) == code)
+}
+
+/// `otpauth://` URI for authenticator apps; render it as a QR code to enrol.
+pub fn provisioning_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = percent_encode(issuer);
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        issuer,
+        percent_encode(account),
+        secret,
+        issuer,
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The ASCII secret "12345678901234567890" from RFC 4226 and RFC 6238, base32-encoded.
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn hotp_matches_rfc4226_vectors() {
+        let secret = BASE32_NOPAD.decode(RFC_SECRET.as_bytes()).unwrap();
+        let expected = [755224, 287082, 359152, 969429, 338314, 254676, 287922, 162583, 399871, 520489];
+        for (counter, code) in expected.into_iter().enumerate() {
+            assert_eq!(hotp(&secret, counter as u64), code, "counter {}", counter);
+        }
+    }
+
+    #[test]
+    fn verify_matches_rfc6238_sha1_vectors() {
+        // Appendix B lists 8-digit values; a 6-digit code is their last six digits
+        let vectors = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+            (20000000000, "353130"),
+        ];
+        for (time, code) in vectors {
+            assert_eq!(verify(RFC_SECRET, code, time), Some(time / STEP_SECONDS), "time {}", time);
+        }
+    }
+
+    #[test]
+    fn accepts_one_step_of_drift_either_way() {
+        // "287082" belongs to step 1 (t = 30..59)
+        assert_eq!(verify(RFC_SECRET, "287082", 59 + STEP_SECONDS), Some(1));
+        assert_eq!(verify(RFC_SECRET, "287082", 59 - STEP_SECONDS), Some(1));
+        assert_eq!(verify(RFC_SECRET, "287082", 59 + 2 * STEP_SECONDS), None);
+    }
+
+    #[test]
+    fn rejects_malformed_codes_and_secrets() {
+        assert_eq!(verify(RFC_SECRET, " 287 082 ", 59), Some(1));
+        assert_eq!(verify(RFC_SECRET, "28708", 59), None);
+        assert_eq!(verify(RFC_SECRET, "2870820", 59), None);
+        assert_eq!(verify(RFC_SECRET, "abcdef", 59), None);
+        assert_eq!(verify("not base32!", "287082", 59), None);
+    }
+
+    #[test]
+    fn provisioning_uri_percent_encodes_labels() {
+        assert_eq!(
+            provisioning_uri("My HP", "admin+1@example.com", "ABC"),
+            "otpauth://totp/My%20HP:admin%2B1%40example.com?secret=ABC&issuer=My%20HP&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn generated_secrets_are_160_bits() {
+        let secret = generate_secret();
+        assert_eq!(BASE32_NOPAD.decode(secret.as_bytes()).unwrap().len(), 20);
+    }
+}
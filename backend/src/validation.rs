@@ -0,0 +1,119 @@
+use serde::Serialize;
+use crate::error::ApiError;
+use crate::models::image::ImageVariants;
+use crate::utils::password::MIN_PASSWORD_LENGTH;
+
+pub const MAX_TITLE_LENGTH: usize = 200;
+pub const MAX_SLUG_LENGTH: usize = 200;
+pub const MAX_PASSWORD_BYTES: usize = 72;
+
+/// A problem with one field of a request body.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+/// Collects field errors so a request reports every problem at once instead of the first.
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the outcome of one of the `validate_*` rules for `field`.
+    pub fn field<E: Into<String>>(&mut self, field: &'static str, result: Result<(), E>) -> &mut Self {
+        if let Err(message) = result {
+            self.add(field, message);
+        }
+        self
+    }
+
+    pub fn add(&mut self, field: &'static str, message: impl Into<String>) -> &mut Self {
+        self.errors.push(FieldError {
+            field,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn has_error(&self, field: &str) -> bool {
+        self.errors.iter().any(|e| e.field == field)
+    }
+
+    pub fn finish(&mut self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::Validation(std::mem::take(&mut self.errors)))
+        }
+    }
+}
+
+pub fn validate_title(title: &str) -> Result<(), String> {
+    let length = title.trim().chars().count();
+    if length == 0 || length > MAX_TITLE_LENGTH {
+        return Err(format!("Title must be between 1 and {} characters", MAX_TITLE_LENGTH));
+    }
+    Ok(())
+}
+
+pub fn validate_markdown(markdown: &str) -> Result<(), String> {
+    if markdown.trim().is_empty() {
+        return Err("Markdown must not be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Slugs use the same alphabet `generate_slug` produces: lowercase letters, digits and
+/// single hyphens between them.
+pub fn validate_slug(slug: &str) -> Result<(), String> {
+    if slug.is_empty() || slug.chars().count() > MAX_SLUG_LENGTH {
+        return Err(format!("Slug must be between 1 and {} characters", MAX_SLUG_LENGTH));
+    }
+    let valid_chars = slug
+        .chars()
+        .all(|c| c == '-' || (c.is_alphanumeric() && !c.is_uppercase()));
+    if !valid_chars || slug.starts_with('-') || slug.ends_with('-') || slug.contains("--") {
+        return Err("Slug may only contain lowercase letters, digits and single hyphens".to_string());
+    }
+    Ok(())
+}
+
+/// Images must live under our own bucket, as returned by `/admin/upload-image`.
+pub fn validate_image_url(url: &str, base_url: &str) -> Result<(), String> {
+    let key = url
+        .strip_prefix(base_url)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .ok_or_else(|| format!("Image URL must point to {}", base_url))?;
+    if key.is_empty() || key.split('/').any(|segment| segment.is_empty() || segment == "..") {
+        return Err("Image URL does not name an object in the bucket".to_string());
+    }
+    Ok(())
+}
+
+/// bcrypt ignores everything past 72 bytes, so longer passwords are refused outright.
+pub fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Password must be at least {} characters", MIN_PASSWORD_LENGTH));
+    }
+    if password.len() > MAX_PASSWORD_BYTES {
+        return Err(format!("Password must be at most {} bytes", MAX_PASSWORD_BYTES));
+    }
+    Ok(())
+}
+
+/// A variant set must describe `image_url` and, like it, point into our own storage.
+pub fn validate_image_variants(variants: &ImageVariants, image_url: Option<&str>, base_url: &str) -> Result<(), String> {
+    if image_url != Some(variants.url.as_str()) {
+        return Err("Image variants must belong to image_url".to_string());
+    }
+    if variants.variants.is_empty() {
+        return Err("Image variants must list at least one width".to_string());
+    }
+    variants.urls().try_for_each(|url| validate_image_url(url, base_url))
+}
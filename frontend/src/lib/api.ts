@@ -2,18 +2,66 @@ const API_BASE = import.meta.env.VITE_API_BASE_URL || 'http://localhost:3000';
 
 export { API_BASE };
 
+const SAFE_METHODS = ['GET', 'HEAD', 'OPTIONS'];
+
+let csrfToken: string | null = null;
+
+async function fetchCsrfToken(fetchFn: typeof fetch): Promise<string | null> {
+  const res = await fetchFn(`${API_BASE}/csrf-token`, { credentials: 'include' });
+  if (!res.ok) return null;
+  const data = await res.json();
+  return data.csrf_token ?? null;
+}
+
+/** Drop the cached CSRF token; it is bound to the session, so call this after login or logout. */
+export function resetCsrfToken() {
+  csrfToken = null;
+}
+
 export async function apiFetch(
   path: string,
   options: RequestInit = {},
   fetchFn: typeof fetch = fetch,
 ) {
-  const res = await fetchFn(`${API_BASE}${path}`, {
-    ...options,
-    credentials: 'include',
-    headers: {
-      'Content-Type': 'application/json',
-      ...options.headers,
-    },
-  });
+  const method = (options.method ?? 'GET').toUpperCase();
+  const needsCsrf = !SAFE_METHODS.includes(method);
+
+  const send = async () => {
+    const headers: Record<string, string> = {};
+    // Let the browser set the multipart boundary for uploads
+    if (!(options.body instanceof FormData)) {
+      headers['Content-Type'] = 'application/json';
+    }
+    if (needsCsrf) {
+      csrfToken ??= await fetchCsrfToken(fetchFn);
+      if (csrfToken) headers['X-CSRF-Token'] = csrfToken;
+    }
+    return fetchFn(`${API_BASE}${path}`, {
+      ...options,
+      credentials: 'include',
+      headers: {
+        ...headers,
+        ...options.headers,
+      },
+    });
+  };
+
+  let res = await send();
+  if (needsCsrf && res.status === 403) {
+    const data = await res.clone().json().catch(() => null);
+    if (data?.code === 'csrf_failed') {
+      // The session changed since the token was fetched; retry once with a fresh one
+      resetCsrfToken();
+      res = await send();
+    }
+  }
   return res;
 }
+
+/** Human-readable message from an API error body, including field-level validation errors. */
+export function errorMessage(data: any, fallback: string): string {
+  if (data?.code === 'validation_failed' && Array.isArray(data.details)) {
+    return data.details.map((e: { field: string; message: string }) => `${e.field}: ${e.message}`).join('\n');
+  }
+  return data?.message ?? fallback;
+}
@@ -0,0 +1,15 @@
+export interface ImageVariant {
+  url: string;
+  width: number;
+  height: number;
+}
+
+/** The set returned by `/admin/upload-image` and stored on books as `image_variants`. */
+export interface ImageVariants {
+  url: string;
+  width: number;
+  height: number;
+  variants: ImageVariant[];
+  thumbnail: ImageVariant;
+  srcset: string;
+}
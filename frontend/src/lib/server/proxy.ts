@@ -0,0 +1,23 @@
+import { env } from '$env/dynamic/private';
+
+const FORWARDED_REQUEST_HEADERS = ['if-none-match', 'if-modified-since'];
+const FORWARDED_RESPONSE_HEADERS = ['content-type', 'etag', 'last-modified', 'cache-control'];
+
+// robots.txt, sitemaps and feeds are generated by the backend but advertised under SITE_URL, this origin
+export async function proxyToBackend(path: string, request: Request, fetchFn: typeof fetch): Promise<Response> {
+	const apiBase = env.INTERNAL_API_URL || 'http://backend:3000';
+	const headers = new Headers();
+	for (const name of FORWARDED_REQUEST_HEADERS) {
+		const value = request.headers.get(name);
+		if (value) headers.set(name, value);
+	}
+
+	const res = await fetchFn(`${apiBase}${path}`, { headers });
+	const responseHeaders = new Headers();
+	for (const name of FORWARDED_RESPONSE_HEADERS) {
+		const value = res.headers.get(name);
+		if (value) responseHeaders.set(name, value);
+	}
+	const body = res.status === 304 ? null : await res.arrayBuffer();
+	return new Response(body, { status: res.status, headers: responseHeaders });
+}
@@ -5,8 +5,9 @@ import type { ServerLoad } from '@sveltejs/kit';
 const API_BASE = process.env.INTERNAL_API_URL || 'http://localhost:3000';
 
 export const load: ServerLoad = async ({ url, cookies }) => {
-  // ログインページ自体はチェック不要
-  if (url.pathname === '/admin/login') return {};
+  // ログイン・パスワード再設定ページはチェック不要
+  const publicPaths = ['/admin/login', '/admin/forgot-password', '/admin/reset-password'];
+  if (publicPaths.includes(url.pathname)) return {};
 
   const sessionId = cookies.get('session_id');
   if (!sessionId) {
@@ -1,10 +1,13 @@
 import { env } from '$env/dynamic/private';
-import { error } from '@sveltejs/kit';
+import { error, redirect } from '@sveltejs/kit';
 import type { PageServerLoad } from './$types';
 
 export const load: PageServerLoad = async ({ fetch, params }) => {
 	const apiBase = env.INTERNAL_API_URL || 'http://backend:3000';
-	const res = await fetch(`${apiBase}/books/${params.slug}`);
+	// Retired slugs answer 301; pass it on so browsers and crawlers see the canonical URL
+	const res = await fetch(`${apiBase}/books/${encodeURIComponent(params.slug)}`, { redirect: 'manual' });
+	const location = res.headers.get('location');
+	if (res.status === 301 && location) throw redirect(301, location);
 	if (res.status === 404) throw error(404, 'Book not found');
 	if (!res.ok) throw error(500, 'Failed to load book');
 	const data = await res.json();
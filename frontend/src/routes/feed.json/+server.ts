@@ -0,0 +1,4 @@
+import { proxyToBackend } from '$lib/server/proxy';
+import type { RequestHandler } from './$types';
+
+export const GET: RequestHandler = ({ request, fetch }) => proxyToBackend('/feed.json', request, fetch);
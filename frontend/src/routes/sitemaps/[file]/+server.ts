@@ -0,0 +1,5 @@
+import { proxyToBackend } from '$lib/server/proxy';
+import type { RequestHandler } from './$types';
+
+export const GET: RequestHandler = ({ params, request, fetch }) =>
+	proxyToBackend(`/sitemaps/${encodeURIComponent(params.file)}`, request, fetch);
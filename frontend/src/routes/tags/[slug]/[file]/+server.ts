@@ -0,0 +1,10 @@
+import { error } from '@sveltejs/kit';
+import { proxyToBackend } from '$lib/server/proxy';
+import type { RequestHandler } from './$types';
+
+const FEED_FILES = ['feed.xml', 'rss.xml', 'feed.json'];
+
+export const GET: RequestHandler = ({ params, request, fetch }) => {
+	if (!FEED_FILES.includes(params.file)) throw error(404, 'Not found');
+	return proxyToBackend(`/tags/${encodeURIComponent(params.slug)}/${params.file}`, request, fetch);
+};